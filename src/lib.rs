@@ -9,40 +9,43 @@ pub use runtime::{
 };
 
 use parser::*;
-use parser::node::Node;
+use parser::node::{Node, Block};
 use runtime::*;
 
 pub type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
 
-pub fn execute(runtime: &mut Runtime, scope: &mut Scope, buffer: String) {
+// parses a whole file, syntax errors include the position they occured at
+pub fn parse(buffer: String) -> Result<Block> {
     let mut parser = Parser::new(buffer);
-    let parsed = parser.parse();
 
-    let node = match parsed {
-        Ok(node) => node,
-        Err(err) => {
-            eprintln!("syntax error: {err} ({}:{})", parser.row(), parser.col());
-            return
-        }
-    };
+    match parser.parse() {
+        Ok(Node::Block(block)) => Ok(block),
+        Ok(_) => unreachable!(),
+        Err(err) => Err(SyntaxError::At(err, parser.row(), parser.col()).into()),
+    }
+}
+
+// parses and evaluates a file in the given scope
+pub fn run(runtime: &mut Runtime, scope: &mut Scope, buffer: String) -> Result<Object> {
+    let block = parse(buffer)?;
 
     // print parsed output
-    // if DEBUG { println!("parsed code:\n{}", node.format(0)); }
+    // if DEBUG { println!("parsed code:\n{}", block.format(0)); }
 
-    // execute the code
-    let res = match node {
-        Node::Block(block) => {
-            block.eval(runtime, scope)
-        }
-        _ => unreachable!()
-    };
+    block.eval(runtime, scope)
+}
 
-    match res {
+// like run, but prints the error instead of returning it
+pub fn execute(runtime: &mut Runtime, scope: &mut Scope, buffer: String) {
+    match run(runtime, scope, buffer) {
         Ok(_) => {
             // if DEBUG {
             //     println!("final objects: {:#?}", scope.objects);
             // }
         }
+        Err(err) if err.is::<SyntaxError>() => {
+            eprintln!("syntax error: {err}");
+        }
         Err(err) => {
             eprintln!("runtime error: {err}");
         }
@@ -15,7 +15,7 @@ fn main() {
     let mut scope = Scope::new(None);
 
     // add functions
-    stdlib::init(&mut runtime.globals);
+    stdlib::init(&mut runtime);
 
     execute(&mut runtime, &mut scope, buffer);
 }
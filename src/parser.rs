@@ -11,11 +11,18 @@ use tokens::*;
 
 use quick_error::quick_error;
 
+// parses the parameters of a native function, written like in scripts
+pub fn parse_params(source: &str) -> Result<Params> {
+    Parser::new(format!("{source})")).read_signature(')')
+}
+
 pub struct Parser {
     chars: Box<[char]>,
     i: usize,
     row: usize,
     col: usize,
+    // where an unclosed block comment starts, which is reported once parsing ends
+    unclosed_comment: Option<(usize, usize, usize)>,
 }
 
 quick_error! {
@@ -29,17 +36,138 @@ quick_error! {
         ExpectedKeyword(keyword: Keyword) {}
         UnexpectedKeyword(keyword: Keyword) {}
         UnexpectedCharacter(ch: char) {}
+        InvalidNumber(number: String) {}
+        InvalidEscape(escape: String) {}
+        // a positional argument after keyword ones, like `f(a = 1, 2)`
+        PositionalAfterKeyword {}
         EOF {}
     }
 }
 
 use ParseError::*;
 
-fn ident_to_str(node: &Node) -> Result<&str> {
+quick_error! {
+    #[derive(Debug)]
+    pub enum SyntaxError {
+        // an error from the parser, with the row and column it occured at
+        At(err: Box<dyn std::error::Error>, row: usize, col: usize) {
+            display("{} ({}:{})", err, row, col)
+        }
+    }
+}
+
+// checks that a node can be assigned to
+fn assignable(node: Node) -> Result<Node> {
     match node {
-        Node::Identifier(ident) => Ok(ident),
-        _ => Err(ExpectedType("Identifier").into())
+        Node::Identifier(..) | Node::Index(..) | Node::Attribute(..) => Ok(node),
+        // several targets, like `a, b = b, a`
+        Node::Tuple(items) => Ok(Node::Tuple(
+            items.into_iter().map(assignable).collect::<Result<_>>()?
+        )),
+        _ => Err(ExpectedType("Identifier, Index or Attribute").into())
+    }
+}
+
+// a single value stays as it is, while several become a tuple
+fn tuple_or_single(mut items: Vec<Node>) -> Node {
+    match items.len() {
+        1 => items.remove(0),
+        _ => Node::Tuple(items),
+    }
+}
+
+// replaces escape sequences like `\n` with the characters they represent
+fn unescape(source: &str) -> Result<String> {
+    let mut string = String::new();
+    let mut chars = source.chars();
+
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            string.push(ch);
+            continue
+        }
+
+        let escape = chars.next().ok_or(InvalidEscape(String::new()))?;
+        string.push(match escape {
+            'n' => '\n',
+            't' => '\t',
+            'r' => '\r',
+            '0' => '\0',
+            '\\' | '"' | '\'' | '{' | '}' => escape,
+            // unicode escapes, like `\u{e9}`
+            'u' => {
+                let code: String = chars.by_ref().take_while(|ch| *ch != '}').collect();
+                code.strip_prefix('{')
+                    .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                    .and_then(char::from_u32)
+                    .ok_or(InvalidEscape(format!("u{code}}}")))?
+            }
+            _ => return Err(InvalidEscape(escape.to_string()).into())
+        });
+    }
+
+    Ok(string)
+}
+
+// reads and parses an expression in an f-string, after the opening brace
+fn read_interpolation(chars: &mut impl Iterator<Item = char>) -> Result<Node> {
+    let mut source = String::new();
+    let mut depth = 1;
+    let mut quote = None;
+
+    // find the closing brace, ignoring braces in nested blocks and strings
+    for ch in chars.by_ref() {
+        match (quote, ch) {
+            (Some(q), ch) if q == ch => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') => quote = Some(ch),
+            (None, '{') => depth += 1,
+            (None, '}') => depth -= 1,
+            _ => {}
+        }
+
+        if depth == 0 {
+            break
+        }
+        source.push(ch);
+    }
+
+    if depth != 0 {
+        return Err(ExpectedToken("}".to_string()).into());
+    }
+
+    let mut parser = Parser::new(source);
+    parser.skip_whitespace()?;
+    let node = parser.read_expression()?;
+
+    match parser.peek() {
+        Ok(ch) => Err(UnexpectedCharacter(*ch).into()),
+        Err(_) => Ok(node),
+    }
+}
+
+// removes the indentation shared by all lines of a multi-line string,
+// as well as the new line after the opening quotes
+// and the indentation before the closing quotes
+fn dedent(source: &str) -> String {
+    let source = source.strip_prefix('\n').unwrap_or(source);
+    let mut lines: Vec<&str> = source.split('\n').collect();
+
+    // the line of the closing quotes
+    if lines.len() > 1 && lines.last().is_some_and(|line| line.trim().is_empty()) {
+        *lines.last_mut().unwrap() = "";
     }
+
+    let indent = lines.iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+
+    lines.iter()
+        .map(|line| line.get(indent..).unwrap_or(line.trim_start()))
+        .collect::<Vec<_>>()
+        .join("\n")
 }
 
 impl Parser {
@@ -49,6 +177,7 @@ impl Parser {
             i: 0,
             row: 0,
             col: 0,
+            unclosed_comment: None,
         }
     }
 
@@ -86,6 +215,10 @@ impl Parser {
         self.chars.get(self.i - 1).ok_or(EOF.into())
     }
 
+    fn peek_is(&self, string: &str) -> bool {
+        self.peekn(string.len()).is_ok_and(|s| s == string)
+    }
+
     fn expect(&mut self, string: &str) -> Result<()> {
         let len = string.len();
         if self.peekn(len)? == string {
@@ -96,6 +229,15 @@ impl Parser {
         }
     }
 
+    // save and restore the position, for when the parser needs to look ahead
+    fn position(&self) -> (usize, usize, usize) {
+        (self.i, self.row, self.col)
+    }
+
+    fn rewind(&mut self, position: (usize, usize, usize)) {
+        (self.i, self.row, self.col) = position;
+    }
+
     fn remaining(&mut self) -> &[char] {
         &self.chars[self.i..]
     }
@@ -103,7 +245,7 @@ impl Parser {
     fn peek_from_chars(&mut self, chars: &'static str) -> String {
         self.remaining().iter()
             .take_while(|c| chars.contains(**c))
-            .map(|c| *c)
+            .copied()
             .collect()
     }
 
@@ -113,45 +255,215 @@ impl Parser {
         result
     }
 
+    // steps over a single character, keeping track of new lines
+    fn step_char(&mut self, ch: char) {
+        self.step();
+        if ch == '\n' {
+            self.col = 0;
+            self.row += 1;
+        }
+    }
+
+    // skips whitespace and comments
     fn skip_whitespace(&mut self) -> Result<()> {
         loop {
-            // TODO fix retarded cloning?
-            if let Ok(ch) = self.peek().cloned() {
-                if ch.is_whitespace() {
+            let ch = *self.peek()?;
+
+            if ch.is_whitespace() {
+                self.step_char(ch);
+            } else if self.peek_is(LINE_COMMENT) {
+                while let Ok(ch) = self.peek() && *ch != '\n' {
                     self.step();
-                    if ch == '\n' {
-                        self.col = 0;
-                        self.row += 1;
-                    }
-                } else {
-                    break Ok(())
                 }
+            } else if self.peek_is(BLOCK_COMMENT_START) {
+                self.skip_block_comment();
             } else {
-                break Err(EOF.into())
+                break Ok(())
             }
         }
     }
 
-    // parse nodes
+    // block comments may be nested, an unclosed one lasts until the end
+    fn skip_block_comment(&mut self) {
+        let start = self.position();
+        let mut depth = 0;
+
+        while let Ok(ch) = self.peek().cloned() {
+            if self.peek_is(BLOCK_COMMENT_START) {
+                self.stepn(2);
+                depth += 1;
+            } else if self.peek_is(BLOCK_COMMENT_END) {
+                self.stepn(2);
+                depth -= 1;
+                if depth == 0 {
+                    return
+                }
+            } else {
+                self.step_char(ch);
+            }
+        }
 
-    fn read_integer(&mut self) -> Result<Node> {
-        let int = Node::Integer(self.next_from_chars(DIGITS).parse().unwrap());
-        Ok(int)
+        self.unclosed_comment.get_or_insert(start);
     }
 
-    fn read_string(&mut self) -> Result<Node> {
-        let terminator = *self.next()?;
-        let result: String = self.remaining().iter()
-            .take_while(|c| **c != terminator)
-            .map(|c| *c)
-            .collect();
-        self.stepn(result.chars().count());
+    // the doc comment on the lines before the given index, if any
+    fn doc_comment(&self, i: usize) -> Option<Box<str>> {
+        let line_start = |end: usize| self.chars[..end].iter().rposition(|ch| *ch == '\n');
+
+        // the lines are read backwards, starting above the one the statement is on
+        let mut end = line_start(i)?;
+        let mut lines = Vec::new();
+
+        loop {
+            let start = line_start(end).map_or(0, |newline| newline + 1);
+            let line = self.chars[start..end].iter().collect::<String>();
+            let line = line.trim();
+
+            if !line.starts_with(DOC_COMMENT) || line.starts_with(NON_DOC_COMMENT) {
+                break
+            }
+            lines.push(line[DOC_COMMENT.len()..].trim_start().to_string());
+
+            if start == 0 {
+                break
+            }
+            end = start - 1;
+        }
+
+        if lines.is_empty() {
+            return None;
+        }
+
+        lines.reverse();
+        Some(lines.join("\n").into())
+    }
+
+    // parse nodes
+
+    // the sign is part of negative literals, so that the smallest integer fits
+    fn read_number(&mut self, negative: bool) -> Result<Node> {
+        let mut number = if negative { OP_SUB.to_string() } else { String::new() };
+        number += &self.next_from_chars(DIGITS);
+        let mut float = false;
 
-        if *self.next()? != terminator {
-            Err(ExpectedToken(terminator.to_string()).into())
+        // a dot not followed by a digit is something else, like a range
+        let rest = self.remaining();
+        if rest.first() == Some(&'.') && rest.get(1).is_some_and(|c| DIGITS.contains(*c)) {
+            self.step();
+            number.push('.');
+            number += &self.next_from_chars(DIGITS);
+            float = true;
+        }
+
+        // exponent, e.g. 1e-3
+        let rest = self.remaining();
+        if matches!(rest.first(), Some('e' | 'E')) {
+            let sign = matches!(rest.get(1), Some('+' | '-')) as usize;
+            if rest.get(1 + sign).is_some_and(|c| DIGITS.contains(*c)) {
+                number.extend(&rest[..1 + sign]);
+                self.stepn(1 + sign);
+                number += &self.next_from_chars(DIGITS);
+                float = true;
+            }
+        }
+
+        if float {
+            Ok(Node::Float(number.parse().map_err(|_| InvalidNumber(number))?))
         } else {
-            Ok(Node::String(result.into()))
+            Ok(Node::Integer(number.parse().map_err(|_| InvalidNumber(number))?))
+        }
+    }
+
+    // reads the source of a string, without handling escapes
+    fn read_string_source(&mut self) -> Result<(String, bool)> {
+        let terminator = *self.peek()?;
+
+        // triple quoted strings may contain lone quotes
+        let triple = terminator.to_string().repeat(3);
+        let multiline = self.peek_is(&triple);
+        let end = if multiline { triple } else { terminator.to_string() };
+        self.stepn(end.len());
+
+        let mut source = String::new();
+        while !self.peek_is(&end) {
+            let ch = *self.peek().map_err(|_| ExpectedToken(end.clone()))?;
+            self.step_char(ch);
+            source.push(ch);
+
+            // an escaped terminator doesn't end the string
+            if ch == '\\' && let Ok(ch) = self.peek().cloned() {
+                self.step_char(ch);
+                source.push(ch);
+            }
+        }
+
+        self.stepn(end.len());
+        Ok((source, multiline))
+    }
+
+    fn read_string(&mut self, raw: bool) -> Result<Node> {
+        let (mut string, multiline) = self.read_string_source()?;
+
+        if multiline {
+            string = dedent(&string);
+        }
+        if !raw {
+            string = unescape(&string)?;
         }
+
+        Ok(Node::String(string.into()))
+    }
+
+    // f-strings, where expressions in braces are formatted into the string,
+    // and double braces are literal braces
+    fn read_format_string(&mut self) -> Result<Node> {
+        let (mut source, multiline) = self.read_string_source()?;
+        if multiline {
+            source = dedent(&source);
+        }
+
+        let mut parts = Vec::new();
+        let mut literal = String::new();
+        let mut chars = source.chars().peekable();
+
+        while let Some(ch) = chars.next() {
+            match ch {
+                '\\' => {
+                    literal.push(ch);
+                    let escape = chars.next();
+                    literal.extend(escape);
+
+                    // unicode escapes contain braces, like `\u{e9}`
+                    if escape == Some('u') && chars.peek() == Some(&'{') {
+                        for ch in chars.by_ref() {
+                            literal.push(ch);
+                            if ch == '}' {
+                                break
+                            }
+                        }
+                    }
+                }
+                '{' | '}' if chars.peek() == Some(&ch) => {
+                    chars.next();
+                    literal.push(ch);
+                }
+                '{' => {
+                    if !literal.is_empty() {
+                        parts.push(Node::String(unescape(&literal)?.into()));
+                        literal.clear();
+                    }
+                    parts.push(read_interpolation(&mut chars)?);
+                }
+                '}' => return Err(UnexpectedCharacter(ch).into()),
+                _ => literal.push(ch),
+            }
+        }
+
+        if !literal.is_empty() {
+            parts.push(Node::String(unescape(&literal)?.into()));
+        }
+
+        Ok(Node::FormatString(parts))
     }
 
     fn read_identifier(&mut self) -> Result<String> {
@@ -161,73 +473,438 @@ impl Parser {
     fn read_operator(&mut self) -> Result<Operator> {
         use Operator::*;
 
-        let op = &*self.next_from_chars(OPERATOR_CHARS);
-
-        Ok(match op {
-            OP_ADD => Add,
-            OP_SUB => Sub,
-            OP_MUL => Mul,
-            OP_DIV => Div,
-            OP_POW => Pow,
-            OP_MOD => Mod,
-            OP_EQUAL => Equal,
-            OP_INEQUAL => Inequal,
-            OP_LESS => Less,
-            OP_LESSEQUAL => LessEqual,
-            OP_GREATER => Greater,
-            OP_GREATEREQUAL => GreaterEqual,
-            OP_AND => And,
-            OP_OR => Or,
-            OP_SETVALUE => SetValue,
-            OP_RANGE_EXCL => RangeExcl,
-            _ => { return Err(InvalidOperator(op.to_string()).into()); }
+        let chars = self.peek_from_chars(OPERATOR_CHARS);
+
+        // the longest matching operator is used, so that
+        // `x=-1` is read as `=` followed by a unary `-`
+        for len in (1..=chars.len()).rev() {
+            let op = match &chars[..len] {
+                OP_ADD => Add,
+                OP_SUB => Sub,
+                OP_MUL => Mul,
+                OP_DIV => Div,
+                OP_POW => Pow,
+                OP_MOD => Mod,
+                OP_EQUAL => Equal,
+                OP_INEQUAL => Inequal,
+                OP_LESS => Less,
+                OP_LESSEQUAL => LessEqual,
+                OP_GREATER => Greater,
+                OP_GREATEREQUAL => GreaterEqual,
+                OP_AND => And,
+                OP_OR => Or,
+                OP_SETVALUE => SetValue,
+                OP_ADD_ASSIGN => AddAssign,
+                OP_SUB_ASSIGN => SubAssign,
+                OP_MUL_ASSIGN => MulAssign,
+                OP_DIV_ASSIGN => DivAssign,
+                OP_MOD_ASSIGN => ModAssign,
+                OP_RANGE_EXCL => RangeExcl,
+                OP_RANGE_INCL => RangeIncl,
+                _ => continue
+            };
+            self.stepn(len);
+            return Ok(op);
+        }
+
+        Err(InvalidOperator(chars).into())
+    }
+
+    fn read_unary(&mut self) -> Result<Node> {
+        let op = match *self.next()? {
+            '-' => UnaryOperator::Neg,
+            '!' => UnaryOperator::Not,
+            _ => unreachable!()
+        };
+
+        // postfix operators bind tighter than the sign, like in `-2.pow(2)`
+        if op == UnaryOperator::Neg && self.peek().is_ok_and(|ch| DIGITS.contains(*ch)) {
+            let position = self.position();
+            let number = self.read_number(true)?;
+            let value = self.read_postfix(number)?;
+            if matches!(value, Node::Integer(_) | Node::Float(_)) && !self.power_follows() {
+                return Ok(value);
+            }
+            self.rewind(position);
+        }
+
+        let mut value = self.read_value()?;
+
+        // powers bind tighter than the sign as well, so `-2 ^ 2` is -4 like in python
+        while op == UnaryOperator::Neg && self.power_follows() {
+            let _ = self.skip_whitespace();
+            self.stepn(OP_POW.len());
+            let _ = self.skip_whitespace();
+            let exponent = self.read_value()?;
+            value = Node::BinaryOp(Box::new(BinaryOp { op: Operator::Pow, a: value, b: exponent }));
+        }
+
+        // negated literals like `-(2)` are folded as well
+        Ok(match (op, value) {
+            (UnaryOperator::Neg, Node::Integer(int)) => Node::Integer(-int),
+            (UnaryOperator::Neg, Node::Float(float)) => Node::Float(-float),
+            (op, value) => Node::UnaryOp(Box::new(UnaryOp { op, value })),
         })
     }
 
+    // whether the next operator is `^`, without moving on
+    fn power_follows(&mut self) -> bool {
+        let position = self.position();
+        let _ = self.skip_whitespace();
+        let follows = self.peekn(OP_POW.len()).is_ok_and(|op| op == OP_POW);
+        self.rewind(position);
+        follows
+    }
+
     fn read_value(&mut self) -> Result<Node> {
         let ch = self.peek()?;
 
+        // unary operators bind tighter than any binary operator,
+        // but the postfix operators are part of the value they apply to
+        if UNARY_OPERATOR_CHARS.contains(*ch) {
+            return self.read_unary();
+        }
+
         let value = if DIGITS.contains(*ch) {
-            self.read_integer()?
+            self.read_number(false)?
         } else if STRING_TERMINATORS.contains(*ch) {
-            self.read_string()?
+            self.read_string(false)?
+        } else if *ch == RAW_STRING_PREFIX && self.chars.get(self.i + 1)
+            .is_some_and(|ch| STRING_TERMINATORS.contains(*ch))
+        {
+            self.step();
+            self.read_string(true)?
+        } else if *ch == FORMAT_STRING_PREFIX && self.chars.get(self.i + 1)
+            .is_some_and(|ch| STRING_TERMINATORS.contains(*ch))
+        {
+            self.step();
+            self.read_format_string()?
         } else if LETTERS.contains(*ch) {
             if let Some(keyword) = self.read_keyword() {
                 match keyword {
                     Keyword::True => Node::Boolean(true),
                     Keyword::False => Node::Boolean(false),
+                    Keyword::Func => self.read_lambda(false)?,
+                    Keyword::Match => self.read_match()?,
+                    Keyword::If => self.read_if()?,
                     _ => { return Err(UnexpectedKeyword(keyword).into()); }
                 }
             } else {
                 Node::Identifier(self.read_identifier()?.into())
             }
         } else if *ch == '(' {
-            self.step();
-            let value = self.read_expression()?;
-            self.expect(")")?;
-            value
+            self.read_parens()?
         } else if *ch == '{' {
-            self.read_block(true)?
+            if self.is_map() {
+                self.read_map()?
+            } else {
+                self.read_block(true)?
+            }
         } else if *ch == '[' {
             self.step();
             Node::List(self.read_args(']')?)
+        } else if *ch == '|' {
+            self.step();
+            self.read_lambda(true)?
         } else {
             return Err(UnexpectedCharacter(*ch).into());
         };
 
-        if self.skip_whitespace().is_err() {
+        self.read_postfix(value)
+    }
+
+    // postfix operators, which may be chained like `f(x)[0]`
+    fn read_postfix(&mut self, mut value: Node) -> Result<Node> {
+        loop {
+            // indexing has to follow the value directly, so that a list on the next
+            // line isn't mistaken for an index, even after a block that was read
+            // together with the whitespace following it
+            if let Ok('[') = self.peek() && !self.chars[self.i - 1].is_whitespace() {
+                self.step();
+                value = self.read_index(value)?;
+                continue
+            }
+
+            let row = self.row;
+            if self.skip_whitespace().is_err() {
+                break
+            }
+
+            match self.peek() {
+                // like indexing, so that parentheses on the next line aren't mistaken for a call
+                Ok('(') if self.row != row => break,
+                Ok('(') => {
+                    self.step();
+                    let (args, kwargs) = self.read_call_args()?;
+                    value = Node::ParenArgs(Box::new(value), args, kwargs);
+                }
+                // `.` followed by another `.` is a range
+                Ok('.') if !self.peek_is(OP_RANGE_EXCL) => {
+                    self.expect(OP_DOT)?;
+                    let _ = self.skip_whitespace();
+                    let name = self.read_identifier()?;
+                    if name.is_empty() {
+                        return Err(ExpectedType("Identifier").into());
+                    }
+                    value = Node::Attribute(Box::new(value), name.into());
+                }
+                _ => break
+            }
+        }
+
+        Ok(value)
+    }
+
+    // reads an expression in parentheses, or a tuple like `()`, `(a,)` or `(a, b)`
+    fn read_parens(&mut self) -> Result<Node> {
+        self.expect("(")?;
+        let _ = self.skip_whitespace();
+
+        if self.peek_is(")") {
+            self.step();
+            return Ok(Node::Tuple(Vec::new()));
+        }
+
+        let value = self.read_expression()?;
+        if !self.peek_is(",") {
+            self.expect(")")?;
             return Ok(value);
         }
 
-        if let Ok(ch) = self.peek() { match ch {
-            '(' => {
+        let mut items = vec![value];
+        while self.peek_is(",") {
+            self.step();
+            let _ = self.skip_whitespace();
+            if self.peek_is(")") {
+                break
+            }
+            items.push(self.read_expression()?);
+        }
+
+        self.expect(")")?;
+        Ok(Node::Tuple(items))
+    }
+
+    // reads `[index]` or a slice, where indexing with a range gives a slice,
+    // and slices with a bound left out like `[start..]` are read separately
+    fn read_index(&mut self, value: Node) -> Result<Node> {
+        let _ = self.skip_whitespace();
+        let value = Box::new(value);
+
+        let start = if self.peek_is(OP_RANGE_EXCL) {
+            None
+        } else {
+            Some(self.read_expression()?)
+        };
+
+        let node = match start {
+            Some(index) if !self.peek_is(OP_RANGE_EXCL) => {
+                Node::Index(value, Box::new(index))
+            }
+            start => {
+                self.expect(OP_RANGE_EXCL)?;
+                self.skip_whitespace()?;
+                let end = match self.peek()? {
+                    ']' => None,
+                    _ => Some(Box::new(self.read_expression()?)),
+                };
+                Node::Slice(value, start.map(Box::new), end)
+            }
+        };
+
+        self.expect("]")?;
+        Ok(node)
+    }
+
+    // checks whether the upcoming braces are a map rather than a block, that is
+    // if they are empty or start with a string, number or name followed by `:`
+    fn is_map(&mut self) -> bool {
+        if self.is_empty_braces() {
+            return true;
+        }
+
+        let position = self.position();
+        self.step();
+        let _ = self.skip_whitespace();
+
+        let key = match self.peek() {
+            Ok(ch) if STRING_TERMINATORS.contains(*ch) => self.read_string_source().is_ok(),
+            Ok('-') => {
                 self.step();
-                return Ok(Node::ParenArgs(Box::new(value), self.read_args(')')?));
+                !self.next_from_chars(DIGITS).is_empty()
             }
-            _ => {}
-        }}
-        
-        Ok(value)
+            _ => !self.next_from_chars(IDENTIFIER_CHARS).is_empty(),
+        };
+        let is_map = key && self.skip_whitespace().is_ok() && self.peek_is(":");
+
+        self.rewind(position);
+        is_map
+    }
+
+    fn is_empty_braces(&mut self) -> bool {
+        let position = self.position();
+        let is_empty = self.expect("{").is_ok() && self.skip_whitespace().is_ok() && self.peek_is("}");
+        self.rewind(position);
+        is_empty
+    }
+
+    fn read_map(&mut self) -> Result<Node> {
+        self.expect("{")?;
+        let _ = self.skip_whitespace();
+        let mut entries = Vec::new();
+
+        loop {
+            if *self.peek()? == '}' {
+                self.step();
+                break
+            }
+
+            let key = self.read_expression()?;
+            self.expect(":")?;
+            let _ = self.skip_whitespace();
+            let value = self.read_expression()?;
+            entries.push((key, value));
+
+            match *self.next()? {
+                ',' => {}
+                '}' => break,
+                ch => return Err(ExpectedTokens(&[",", "}"], ch.to_string()).into())
+            }
+
+            let _ = self.skip_whitespace();
+        }
+
+        let _ = self.skip_whitespace();
+        Ok(Node::Map(entries))
+    }
+
+    // reads `match value { pattern if guard => body, ... }`
+    fn read_match(&mut self) -> Result<Node> {
+        let value = self.read_expression()?;
+        self.expect("{")?;
+        let _ = self.skip_whitespace();
+        let mut arms = Vec::new();
+
+        while *self.peek()? != '}' {
+            let pattern = self.read_pattern()?;
+
+            let position = self.position();
+            let guard = match self.read_keyword() {
+                Some(Keyword::If) => Some(self.read_expression()?),
+                _ => {
+                    self.rewind(position);
+                    None
+                }
+            };
+
+            self.expect(MATCH_ARROW)?;
+            let _ = self.skip_whitespace();
+            // an arm doing nothing, rather than giving an empty map
+            let body = if self.is_empty_braces() {
+                self.read_block(true)?
+            } else {
+                self.read_expression()?
+            };
+            arms.push(MatchArm { pattern, guard, body });
+
+            // arms are separated by commas or newlines
+            let _ = self.skip_whitespace();
+            if self.peek_is(",") {
+                self.step();
+                let _ = self.skip_whitespace();
+            }
+        }
+
+        self.step();
+        Ok(Node::Match(Box::new(value), arms))
+    }
+
+    // reads a pattern, which may be several alternatives separated by `|`
+    fn read_pattern(&mut self) -> Result<Pattern> {
+        let mut alternatives = vec![self.read_single_pattern()?];
+
+        while self.peek_is(PATTERN_OR) && !self.peek_is(OP_OR) {
+            self.step();
+            let _ = self.skip_whitespace();
+            alternatives.push(self.read_single_pattern()?);
+        }
+
+        Ok(match alternatives.len() {
+            1 => alternatives.remove(0),
+            _ => Pattern::Alternatives(alternatives),
+        })
+    }
+
+    fn read_single_pattern(&mut self) -> Result<Pattern> {
+        let ch = *self.peek()?;
+
+        let pattern = if ch == '[' {
+            self.step();
+            let _ = self.skip_whitespace();
+            let mut items = Vec::new();
+
+            while *self.peek()? != ']' {
+                items.push(self.read_pattern()?);
+                match *self.peek()? {
+                    ',' => {
+                        self.step();
+                        let _ = self.skip_whitespace();
+                    }
+                    ']' => {}
+                    ch => return Err(ExpectedTokens(&[",", "]"], ch.to_string()).into())
+                }
+            }
+
+            self.step();
+            Pattern::List(items)
+
+        } else if ch == '(' {
+            self.step();
+            let _ = self.skip_whitespace();
+            let mut items = Vec::new();
+
+            while *self.peek()? != ')' {
+                items.push(self.read_pattern()?);
+                match *self.peek()? {
+                    ',' => {
+                        self.step();
+                        let _ = self.skip_whitespace();
+                    }
+                    ')' => {}
+                    ch => return Err(ExpectedTokens(&[",", ")"], ch.to_string()).into())
+                }
+            }
+
+            self.step();
+            Pattern::Tuple(items)
+
+        } else if ch == '_' || LETTERS.contains(ch) && self.peek_keyword().is_none() {
+            let ident = self.read_identifier()?;
+            if ident == PATTERN_WILDCARD {
+                Pattern::Wildcard
+            } else {
+                Pattern::Binding(ident.into())
+            }
+
+        } else {
+            match self.read_value()? {
+                Node::Integer(start) if self.peek_is(OP_RANGE_EXCL) => {
+                    let inclusive = self.read_operator()? == Operator::RangeIncl;
+                    let _ = self.skip_whitespace();
+                    match self.read_value()? {
+                        Node::Integer(end) => Pattern::Range(start, end, inclusive),
+                        _ => return Err(ExpectedType("Integer").into())
+                    }
+                }
+                value @ (Node::Integer(_) | Node::Float(_) | Node::String(_) | Node::Boolean(_)) => {
+                    Pattern::Literal(Box::new(value))
+                }
+                _ => return Err(ExpectedType("Pattern").into())
+            }
+        };
+
+        let _ = self.skip_whitespace();
+        Ok(pattern)
     }
 
     fn read_args(&mut self, term: char) -> Result<Vec<Node>> {
@@ -236,7 +913,6 @@ impl Parser {
 
         if *self.peek()? == term {
             self.step();
-            let _  = self.skip_whitespace();
             return Ok(args);
         }
 
@@ -247,7 +923,6 @@ impl Parser {
             let ch = *self.next()?;
 
             if ch == term {
-                let _  = self.skip_whitespace();
                 return Ok(args);
             } else if ch != ',' {
                 return Err(ExpectedTokens(&[",", ")"], ch.to_string()).into());
@@ -257,6 +932,51 @@ impl Parser {
         }
     }
 
+    // reads the arguments of a call, where keyword arguments look like `name = value`
+    fn read_call_args(&mut self) -> Result<(Vec<Node>, KeywordArgs)> {
+        let _ = self.skip_whitespace();
+        let mut args = Vec::new();
+        let mut kwargs = Vec::new();
+
+        if *self.peek()? == ')' {
+            self.step();
+            return Ok((args, kwargs));
+        }
+
+        loop {
+            match self.read_keyword_arg() {
+                Some(name) => kwargs.push((name, self.read_expression()?)),
+                None if !kwargs.is_empty() => return Err(PositionalAfterKeyword.into()),
+                None => args.push(self.read_expression()?),
+            }
+
+            match *self.next()? {
+                ')' => return Ok((args, kwargs)),
+                ',' => {}
+                ch => return Err(ExpectedTokens(&[",", ")"], ch.to_string()).into())
+            }
+
+            let _ = self.skip_whitespace();
+        }
+    }
+
+    // reads the `name =` of a keyword argument, if there is one
+    fn read_keyword_arg(&mut self) -> Option<Box<str>> {
+        let position = self.position();
+        let ident = self.peek_from_chars(IDENTIFIER_CHARS);
+        self.stepn(ident.len());
+        let _ = self.skip_whitespace();
+
+        if !ident.is_empty() && self.peek_is(OP_SETVALUE) && !self.peek_is(OP_EQUAL) {
+            self.stepn(OP_SETVALUE.len());
+            let _ = self.skip_whitespace();
+            Some(ident.into())
+        } else {
+            self.rewind(position);
+            None
+        }
+    }
+
     fn read_expression(&mut self) -> Result<Node> {
         let mut values: Vec<Node> = Vec::new();
         let mut operators: Vec<Operator> = Vec::new();
@@ -270,13 +990,30 @@ impl Parser {
 
             let ch = self.peek()?;
 
+            // the end of a guard in a match arm
+            if self.peek_is(MATCH_ARROW) {
+                break
+            }
+
             if OPERATOR_CHARS.contains(*ch) {
+                let position = self.position();
                 let op = self.read_operator()?;
 
                 self.skip_whitespace()?;
 
+                // a slice with an open end, like `list[1..]`
+                if op == Operator::RangeExcl && *self.peek()? == ']' {
+                    self.rewind(position);
+                    break
+                }
+
                 operators.push(op);
 
+            // `in` is the only operator that's a keyword
+            } else if matches!(self.peek_keyword(), Some(Keyword::In)) {
+                self.read_keyword();
+                operators.push(Operator::In);
+
             } else {
                 break
             }
@@ -284,7 +1021,7 @@ impl Parser {
 
         // TODO this is probably pretty inefficient:
 
-        while 0 < operators.len() {
+        while !operators.is_empty() {
             'levels: for level in OPERATOR_ORDER {
                 for target_op in level.iter() {
                     for (i, op) in operators.clone().iter().enumerate() {
@@ -293,9 +1030,10 @@ impl Parser {
                                 .collect::<Vec<_>>().try_into().unwrap();
                             let _ = operators.remove(i);
 
-                            let a = match op {
-                                Operator::SetValue => Node::String(ident_to_str(&a)?.into()),
-                                _ => a
+                            let a = if op.is_assignment() {
+                                assignable(a)?
+                            } else {
+                                a
                             };
 
                             values.insert(i, Node::BinaryOp(Box::new(BinaryOp {
@@ -317,6 +1055,14 @@ impl Parser {
     }
 
     fn read_let(&mut self) -> Result<Node> {
+        if self.peek_is("[") || self.peek_is("(") {
+            let pattern = self.read_single_pattern()?;
+            self.expect("=")?;
+            self.skip_whitespace()?;
+            let value = self.read_expression()?;
+            return Ok(Node::Statement(Statement::Destructure(pattern, Box::new(value))));
+        }
+
         let ident = self.read_identifier()?;
         self.skip_whitespace()?;
 
@@ -328,45 +1074,188 @@ impl Parser {
         Ok(Node::Statement(Statement::DefineVariable(ident, Box::new(value))))
     }
 
-    fn read_func(&mut self) -> Result<Node> {
+    fn read_params(&mut self, term: char) -> Result<Vec<Box<str>>> {
+        let mut params = Vec::new();
+        let _ = self.skip_whitespace();
+
+        while *self.peek()? != term {
+            let ident = self.read_identifier()?;
+            if ident.is_empty() {
+                return Err(ExpectedType("Identifier").into());
+            }
+            params.push(ident.into());
+            let _ = self.skip_whitespace();
+
+            match *self.peek()? {
+                ',' => {
+                    self.step();
+                    let _ = self.skip_whitespace();
+                }
+                ch if ch == term => {}
+                ch => return Err(ExpectedTokens(&[",", ")"], ch.to_string()).into())
+            }
+        }
+
+        self.step();
+        let _ = self.skip_whitespace();
+        Ok(params)
+    }
+
+    fn read_name(&mut self) -> Result<Box<str>> {
         let ident = self.read_identifier()?;
+        if ident.is_empty() {
+            return Err(ExpectedType("Identifier").into());
+        }
         let _ = self.skip_whitespace();
-        self.expect("(")?;
+        Ok(ident.into())
+    }
 
-        let args = self.read_args(')')?;
-        let args: Vec<Box<str>> = args.iter().map(
-            |arg| ident_to_str(arg).unwrap().into() // TODO fix
-        ).collect();
+    // reads the parameters of a function up to the terminator,
+    // like `a, b = 2, *rest, c = 3, **options`
+    fn read_signature(&mut self, term: char) -> Result<Params> {
+        let mut params = Params::default();
+        let _ = self.skip_whitespace();
 
-        let block = self.read_block(true)?;
-        let block = match block {
-            Node::Block(block) => block,
+        while *self.peek()? != term {
+            // nothing may follow the options
+            if params.options.is_some() {
+                return Err(UnexpectedCharacter(*self.peek()?).into());
+            }
+
+            if self.peek_is(PARAM_OPTIONS) {
+                self.stepn(PARAM_OPTIONS.len());
+                params.options = Some(self.read_name()?);
+            } else if self.peek_is(PARAM_REST) && params.rest.is_none() {
+                self.stepn(PARAM_REST.len());
+                params.rest = Some(self.read_name()?);
+            } else {
+                let name = self.read_name()?;
+                let default = if self.peek_is(OP_SETVALUE) && !self.peek_is(OP_EQUAL) {
+                    self.stepn(OP_SETVALUE.len());
+                    let _ = self.skip_whitespace();
+                    // the terminator of short lambdas would be read as an operator
+                    Some(if term == '|' { self.read_value()? } else { self.read_expression()? })
+                } else {
+                    None
+                };
+
+                let param = Param { name, default };
+                match params.rest {
+                    Some(_) => params.keyword.push(param),
+                    None => params.positional.push(param),
+                }
+            }
+
+            match *self.peek()? {
+                ',' => {
+                    self.step();
+                    let _ = self.skip_whitespace();
+                }
+                ch if ch == term => {}
+                ch => return Err(ExpectedTokens(&[",", ")"], ch.to_string()).into())
+            }
+        }
+
+        self.step();
+        let _ = self.skip_whitespace();
+        Ok(params)
+    }
+
+    fn read_func_block(&mut self) -> Result<Block> {
+        match self.read_block(true)? {
+            Node::Block(block) => Ok(block),
             _ => unreachable!()
+        }
+    }
+
+    fn read_func(&mut self, doc: Option<Box<str>>) -> Result<Node> {
+        let ident = self.read_identifier()?;
+        let _ = self.skip_whitespace();
+        self.expect("(")?;
+
+        let args = self.read_signature(')')?;
+        let block = self.read_func_block()?;
+
+        Ok(Node::Statement(Statement::DefineFunction(ident, args, block, doc)))
+    }
+
+    // anonymous functions, either `fn(args) { ... }` or `|args| expression`
+    fn read_lambda(&mut self, short: bool) -> Result<Node> {
+        if short {
+            let args = self.read_signature('|')?;
+            // the body is a single expression, which is returned
+            let value = self.read_expression()?;
+            let ret = Node::Statement(Statement::Return(Some(Box::new(value))));
+            Ok(Node::Function(args, Block::new(vec![ret])))
+        } else {
+            self.expect("(")?;
+            let args = self.read_signature(')')?;
+            Ok(Node::Function(args, self.read_func_block()?))
+        }
+    }
+
+    fn read_struct(&mut self) -> Result<Node> {
+        let ident = self.read_identifier()?;
+        let _ = self.skip_whitespace();
+        self.expect("{")?;
+
+        let fields = self.read_params('}')?;
+
+        Ok(Node::Statement(Statement::DefineStruct(ident, fields)))
+    }
+
+    fn read_class(&mut self) -> Result<Node> {
+        let ident = self.read_identifier()?;
+        let _ = self.skip_whitespace();
+
+        let parent = if self.peek_is("(") {
+            self.step();
+            let _ = self.skip_whitespace();
+            let parent = self.read_identifier()?;
+            let _ = self.skip_whitespace();
+            self.expect(")")?;
+            let _ = self.skip_whitespace();
+            Some(parent.into())
+        } else {
+            None
         };
 
-        Ok(Node::Statement(Statement::DefineFunction(ident, args, block)))
+        let block = self.read_func_block()?;
+
+        // the body may only define methods
+        for statement in block.statements.iter() {
+            if !matches!(statement, Node::Statement(Statement::DefineFunction(..))) {
+                return Err(ExpectedKeyword(Keyword::Func).into());
+            }
+        }
+
+        Ok(Node::Statement(Statement::DefineClass(ident, parent, block)))
     }
 
     fn read_if(&mut self) -> Result<Node> {
         let condition = self.read_expression()?;
         let block = self.read_block(true)?;
 
-        let ext: Option<Box<Node>> = if let Some(kw) = self.read_keyword() {
-            match kw {
-                Keyword::Else => Some(Box::new(self.read_block(true)?)),
-                Keyword::Elif => Some(Box::new(self.read_if()?)),
-                _ => None
+        let position = self.position();
+        let ext: Option<Box<Node>> = match self.read_keyword() {
+            Some(Keyword::Else) => Some(Box::new(self.read_block(true)?)),
+            Some(Keyword::Elif) => Some(Box::new(self.read_if()?)),
+            _ => {
+                // the keyword belongs to the next statement
+                self.rewind(position);
+                None
             }
-        } else {
-            None
         };
 
         Ok(Node::Statement(Statement::If(Box::new(condition), Box::new(block), ext)))
     }
 
     fn read_for(&mut self) -> Result<Node> {
-        let ident = self.read_identifier()?;
-        let _ = self.skip_whitespace();
+        let pattern = if self.peek_is("[") || self.peek_is("(") {
+            self.read_single_pattern()?
+        } else {
+            Pattern::Binding(self.read_name()?)
+        };
 
         match self.read_keyword() {
             Some(Keyword::In) => {}
@@ -377,14 +1266,142 @@ impl Parser {
         let block = self.read_block(true)?;
 
         Ok(Node::Statement(Statement::For(
-            ident.into(),
+            pattern,
             Box::new(sequence),
             Box::new(block)
         )))
     }
 
+    // reads `try { } catch name { } finally { }`, where either
+    // the catch or the finally block may be left out, as well as the name
+    fn read_try(&mut self) -> Result<Node> {
+        let block = self.read_block(true)?;
+
+        let position = self.position();
+        let catch = match self.read_keyword() {
+            Some(Keyword::Catch) => {
+                let ident = self.read_identifier()?;
+                let _ = self.skip_whitespace();
+                let ident = (!ident.is_empty()).then(|| ident.into());
+                Some((ident, Box::new(self.read_block(true)?)))
+            }
+            _ => {
+                self.rewind(position);
+                None
+            }
+        };
+
+        let position = self.position();
+        let finally = match self.read_keyword() {
+            Some(Keyword::Finally) => Some(Box::new(self.read_block(true)?)),
+            _ if catch.is_none() => return Err(ExpectedKeyword(Keyword::Catch).into()),
+            _ => {
+                self.rewind(position);
+                None
+            }
+        };
+
+        Ok(Node::Statement(Statement::Try(Box::new(block), catch, finally)))
+    }
+
+    fn read_path(&mut self) -> Result<Box<str>> {
+        match *self.peek()? {
+            ch if STRING_TERMINATORS.contains(ch) => match self.read_string(false)? {
+                Node::String(path) => {
+                    let _ = self.skip_whitespace();
+                    Ok(path)
+                }
+                _ => unreachable!()
+            }
+            _ => Err(ExpectedType("String").into())
+        }
+    }
+
+    fn read_import(&mut self) -> Result<Node> {
+        let path = self.read_path()?;
+
+        match self.read_keyword() {
+            Some(Keyword::As) => {}
+            _ => return Err(ExpectedKeyword(Keyword::As).into())
+        }
+
+        let ident = self.read_identifier()?;
+        if ident.is_empty() {
+            return Err(ExpectedType("Identifier").into());
+        }
+
+        Ok(Node::Statement(Statement::Import(path, ident.into())))
+    }
+
+    fn read_import_from(&mut self) -> Result<Node> {
+        let path = self.read_path()?;
+
+        match self.read_keyword() {
+            Some(Keyword::Import) => {}
+            _ => return Err(ExpectedKeyword(Keyword::Import).into())
+        }
+
+        let mut names = Vec::new();
+        loop {
+            let ident = self.read_identifier()?;
+            if ident.is_empty() {
+                return Err(ExpectedType("Identifier").into());
+            }
+            names.push(ident.into());
+
+            // the names end with the line
+            let position = self.position();
+            let _ = self.skip_whitespace();
+            if self.peek_is(",") {
+                self.step();
+                let _ = self.skip_whitespace();
+            } else {
+                self.rewind(position);
+                break
+            }
+        }
+
+        Ok(Node::Statement(Statement::ImportFrom(path, names)))
+    }
+
+    fn read_while(&mut self) -> Result<Node> {
+        let condition = self.read_expression()?;
+        let block = self.read_block(true)?;
+
+        Ok(Node::Statement(Statement::While(Box::new(condition), Box::new(block))))
+    }
+
+    fn read_return(&mut self) -> Result<Node> {
+        // a bare return is followed by the end of the block or file
+        let value = match self.peek() {
+            Ok('}') | Err(_) => None,
+            // several values are returned as a tuple
+            Ok(_) => Some(Box::new(self.read_expression_list()?)),
+        };
+
+        Ok(Node::Statement(Statement::Return(value)))
+    }
+
+    fn read_yield(&mut self) -> Result<Node> {
+        let value = match self.peek() {
+            Ok('}') | Err(_) => None,
+            Ok(_) => Some(Box::new(self.read_expression_list()?)),
+        };
+
+        Ok(Node::Statement(Statement::Yield(value)))
+    }
+
+    // checks whether the upcoming word is a keyword, without reading it
+    fn peek_keyword(&mut self) -> Option<Keyword> {
+        let position = self.position();
+        let keyword = self.read_keyword();
+        self.rewind(position);
+        keyword
+    }
+
     fn read_keyword(&mut self) -> Option<Keyword> {
-        let potential_keyword = &*self.peek_from_chars(LOWERCASE_LETTERS);
+        // read the whole word, so that identifiers like `in_range` aren't split
+        let potential_keyword = &*self.peek_from_chars(IDENTIFIER_CHARS);
 
         if KEYWORDS.contains(&potential_keyword) {
             self.stepn(potential_keyword.len());
@@ -400,6 +1417,22 @@ impl Parser {
                 KW_FALSE => Keyword::False,
                 KW_FOR => Keyword::For,
                 KW_IN => Keyword::In,
+                KW_RETURN => Keyword::Return,
+                KW_WHILE => Keyword::While,
+                KW_BREAK => Keyword::Break,
+                KW_CONTINUE => Keyword::Continue,
+                KW_STRUCT => Keyword::Struct,
+                KW_CLASS => Keyword::Class,
+                KW_MATCH => Keyword::Match,
+                KW_THROW => Keyword::Throw,
+                KW_TRY => Keyword::Try,
+                KW_CATCH => Keyword::Catch,
+                KW_FINALLY => Keyword::Finally,
+                KW_IMPORT => Keyword::Import,
+                KW_FROM => Keyword::From,
+                KW_AS => Keyword::As,
+                KW_EXPORT => Keyword::Export,
+                KW_YIELD => Keyword::Yield,
                 _ => unreachable!()
             })
         }
@@ -408,17 +1441,93 @@ impl Parser {
     }
 
     fn read_statement(&mut self) -> Result<Node> {
+        let position = self.position();
+
         if let Some(keyword) = self.read_keyword() {
             match keyword {
                 Keyword::Let => return self.read_let(),
                 Keyword::If => return self.read_if(),
-                Keyword::Func => return self.read_func(),
+                // anonymous functions are expressions
+                Keyword::Func if self.peek().is_ok_and(|ch| *ch == '(') => {
+                    self.rewind(position);
+                }
+                Keyword::Match => self.rewind(position),
+                Keyword::Func => return self.read_func(self.doc_comment(position.0)),
                 Keyword::For => return self.read_for(),
+                Keyword::While => return self.read_while(),
+                Keyword::Struct => return self.read_struct(),
+                Keyword::Class => return self.read_class(),
+                Keyword::Return => return self.read_return(),
+                Keyword::Yield => return self.read_yield(),
+                Keyword::Throw => {
+                    let value = self.read_expression()?;
+                    return Ok(Node::Statement(Statement::Throw(Box::new(value))));
+                }
+                Keyword::Try => return self.read_try(),
+                Keyword::Import => return self.read_import(),
+                Keyword::From => return self.read_import_from(),
+                Keyword::Export => {
+                    let statement = self.read_statement()?;
+                    return match statement {
+                        Node::Statement(ref inner) if inner.defined_name().is_some() => {
+                            Ok(Node::Statement(Statement::Export(Box::new(statement))))
+                        }
+                        _ => Err(ExpectedKeyword(Keyword::Let).into())
+                    }
+                }
+                Keyword::Break => return Ok(Node::Statement(Statement::Break)),
+                Keyword::Continue => return Ok(Node::Statement(Statement::Continue)),
                 _ => { return Err(UnexpectedKeyword(keyword).into()) }
             }
         }
 
-        self.read_expression()
+        let start = self.position();
+        let value = self.read_expression_list()?;
+
+        // assigning to several targets, like `a, b = b, a`,
+        // where the assignment has been read as part of one of the values
+        if let Node::Tuple(items) = &value
+            && let Some(i) = items.iter().position(|item| matches!(item,
+                Node::BinaryOp(op) if op.op == Operator::SetValue))
+        {
+            let mut targets = items.clone();
+            let mut values = targets.split_off(i + 1);
+            let Some(Node::BinaryOp(op)) = targets.pop() else { unreachable!() };
+            targets.push(op.a);
+            values.insert(0, op.b);
+
+            return Ok(Node::BinaryOp(Box::new(BinaryOp {
+                op: Operator::SetValue,
+                a: assignable(tuple_or_single(targets))?,
+                b: tuple_or_single(values),
+            })));
+        }
+
+        // compound assignments only have a single target, `a, b += 1, 2` would
+        // otherwise be a tuple where only `b` is changed
+        if let Node::Tuple(items) = &value
+            && let Some(Node::BinaryOp(op)) = items.iter().find(|item| matches!(item,
+                Node::BinaryOp(op) if op.op.is_assignment()))
+        {
+            // reported at the statement, rather than after it
+            self.rewind(start);
+            return Err(InvalidOperator(op.op.format(0)).into());
+        }
+
+        Ok(value)
+    }
+
+    // reads expressions separated by commas, which are a tuple if there are several
+    fn read_expression_list(&mut self) -> Result<Node> {
+        let mut items = vec![self.read_expression()?];
+
+        while self.peek_is(",") {
+            self.step();
+            let _ = self.skip_whitespace();
+            items.push(self.read_expression()?);
+        }
+
+        Ok(tuple_or_single(items))
     }
 
     fn read_block(&mut self, inner: bool) -> Result<Node> {
@@ -429,9 +1538,21 @@ impl Parser {
         let mut nodes = Vec::new();
 
         if self.skip_whitespace().is_ok() {
+            // empty blocks
+            if inner && let Ok('}') = self.peek() {
+                self.step();
+                let _ = self.skip_whitespace();
+                return Ok(Node::Block(Block::new(nodes)));
+            }
+
             loop {
                 nodes.push(self.read_statement()?);
 
+                // statements on the same line are separated by semicolons
+                if self.peek_is(STATEMENT_SEPARATOR) {
+                    self.step();
+                }
+
                 // match self.next()?.clone() {
                 //     ';' => {}
                 //     ch => {
@@ -442,26 +1563,29 @@ impl Parser {
                 if self.skip_whitespace().is_err() {
                     break
 
-                } else if inner {
-                    if let Ok(ch) = self.peek() {
-                        if *ch == '}' {
-                            self.step();
-                            break
-                        }
-                    }
+                } else if inner && let Ok('}') = self.peek() {
+                    self.step();
+                    break
                 }
             }
         }
 
         let _ = self.skip_whitespace();
 
-        return Ok(Node::Block(Block::new(nodes)))
+        Ok(Node::Block(Block::new(nodes)))
     }
 
     // parse the whole buffer
 
     pub fn parse(&mut self) -> Result<Node> {
-        let block = self.read_block(false)?;
+        let block = self.read_block(false);
+
+        // it swallows the rest of the file, so it's reported instead of what follows from that
+        if let Some(position) = self.unclosed_comment {
+            self.rewind(position);
+            return Err(ExpectedToken(BLOCK_COMMENT_END.to_string()).into());
+        }
+        let block = block?;
 
         if self.peek().is_ok() {
             panic!("Unread chars starting at index {}", self.i);
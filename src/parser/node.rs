@@ -1,19 +1,41 @@
 use super::tokens::*;
+use std::cell::OnceCell;
+use std::rc::Rc;
 
 #[derive(Debug, Clone)]
 pub enum Node {
     Statement(Statement),
     Block(Block),
-    ParenArgs(Box<Node>, Vec<Node>),
+    ParenArgs(Box<Node>, Vec<Node>, KeywordArgs),
+    Index(Box<Node>, Box<Node>),
+    Attribute(Box<Node>, Box<str>),
+    Slice(Box<Node>, Option<Box<Node>>, Option<Box<Node>>),
+    Function(Params, Block),
+    Match(Box<Node>, Vec<MatchArm>),
 
     // Operators
+    UnaryOp(Box<UnaryOp>),
     BinaryOp(Box<BinaryOp>),
 
     Identifier(Box<str>),
     String(Box<str>),
+    // the parts are either literal strings or expressions
+    FormatString(Vec<Node>),
     Integer(i32),
+    Float(f64),
     Boolean(bool),
     List(Vec<Node>),
+    Tuple(Vec<Node>),
+    Map(Vec<(Node, Node)>),
+}
+
+// the keyword arguments of a call, like `f(name = value)`
+pub type KeywordArgs = Vec<(Box<str>, Node)>;
+
+#[derive(Debug, Clone)]
+pub struct UnaryOp {
+    pub op: UnaryOperator,
+    pub value: Node,
 }
 
 #[derive(Debug, Clone)]
@@ -23,24 +45,166 @@ pub struct BinaryOp {
     pub b: Node,
 }
 
+#[derive(Debug, Clone)]
+pub struct Param {
+    pub name: Box<str>,
+    // evaluated when the function is called without the argument
+    pub default: Option<Node>,
+}
+
+// the parameters of a function, like `a, b = 2, *rest, c = 3, **options`
+#[derive(Debug, Clone, Default)]
+pub struct Params {
+    pub positional: Vec<Param>,
+    // collects the remaining positional arguments into a list
+    pub rest: Option<Box<str>>,
+    // parameters after the rest can only be given by keyword
+    pub keyword: Vec<Param>,
+    // collects the remaining keyword arguments into a map
+    pub options: Option<Box<str>>,
+}
+
+impl Params {
+    // the parameters left after binding the first one, as for methods
+    pub fn without_first(&self) -> Self {
+        let mut params = self.clone();
+        if !params.positional.is_empty() {
+            params.positional.remove(0);
+        }
+        params
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MatchArm {
+    pub pattern: Pattern,
+    pub guard: Option<Node>,
+    pub body: Node,
+}
+
+#[derive(Debug, Clone)]
+pub enum Pattern {
+    Wildcard,
+    // binds the value to a name in the scope of the arm
+    Binding(Box<str>),
+    // a string, number or boolean
+    Literal(Box<Node>),
+    // the bool is whether the range is inclusive
+    Range(i32, i32, bool),
+    // list and tuple patterns match both lists and tuples
+    List(Vec<Pattern>),
+    Tuple(Vec<Pattern>),
+    Alternatives(Vec<Pattern>),
+}
+
 #[derive(Debug, Clone)]
 pub enum Statement {
     // TODO replace String with Box<str>
     DefineVariable(String, Box<Node>),
-    DefineFunction(String, Vec<Box<str>>, Block),
+    // `let [a, b] = value` or `let (a, b) = value`
+    Destructure(Pattern, Box<Node>),
+    // the last field is the doc comment
+    DefineFunction(String, Params, Block, Option<Box<str>>),
+    DefineStruct(String, Vec<Box<str>>),
+    DefineClass(String, Option<Box<str>>, Block),
     If(Box<Node>, Box<Node>, Option<Box<Node>>),
-    For(Box<str>, Box<Node>, Box<Node>),
+    For(Pattern, Box<Node>, Box<Node>),
+    While(Box<Node>, Box<Node>),
+    Return(Option<Box<Node>>),
+    Yield(Option<Box<Node>>),
+    // `import "path" as name`
+    Import(Box<str>, Box<str>),
+    // `from "path" import names`
+    ImportFrom(Box<str>, Vec<Box<str>>),
+    // the statement is always a definition
+    Export(Box<Node>),
+    Throw(Box<Node>),
+    // the catch block has an optional name for the error
+    Try(Box<Node>, Option<(Option<Box<str>>, Box<Node>)>, Option<Box<Node>>),
+    Break,
+    Continue,
 }
 
 #[derive(Debug, Clone)]
 pub struct Block {
-    pub statements: Vec<Node>,
+    // shared, so that functions and generators don't copy the statements
+    pub statements: Rc<[Node]>,
+    // which of the statements contain `yield`, found the first time it's asked
+    yields: OnceCell<Rc<[bool]>>,
 }
 
 impl Block {
     pub fn new(statements: Vec<Node>) -> Self {
         Self {
-            statements,
+            statements: statements.into(),
+            yields: OnceCell::new(),
+        }
+    }
+
+    // the names exported at the top level of the block
+    pub fn exports(&self) -> Vec<Box<str>> {
+        self.statements.iter()
+            .filter_map(|node| match node {
+                Node::Statement(Statement::Export(statement)) => match &**statement {
+                    Node::Statement(statement) => statement.defined_name(),
+                    _ => None,
+                }
+                _ => None,
+            })
+            .map(Into::into)
+            .collect()
+    }
+}
+
+impl Block {
+    // whether the block yields, which makes the function it belongs to a generator
+    pub fn contains_yield(&self) -> bool {
+        self.yields().contains(&true)
+    }
+
+    // the nested blocks remember their own, so this only walks the block once
+    pub fn yields(&self) -> &[bool] {
+        self.yields.get_or_init(|| self.statements.iter().map(Node::contains_yield).collect())
+    }
+}
+
+impl Node {
+    // functions defined inside don't count, as they are generators of their own
+    pub fn contains_yield(&self) -> bool {
+        match self {
+            Self::Statement(statement) => statement.contains_yield(),
+            Self::Block(block) => block.contains_yield(),
+            Self::Match(_, arms) => arms.iter().any(|arm| arm.body.contains_yield()),
+            _ => false,
+        }
+    }
+}
+
+impl Statement {
+    pub fn contains_yield(&self) -> bool {
+        match self {
+            Self::Yield(_) => true,
+            Self::If(_, block, ext) => {
+                block.contains_yield() || ext.as_ref().is_some_and(|ext| ext.contains_yield())
+            }
+            Self::For(_, _, block) | Self::While(_, block) => block.contains_yield(),
+            Self::Try(block, catch, finally) => {
+                block.contains_yield()
+                    || catch.as_ref().is_some_and(|(_, handler)| handler.contains_yield())
+                    || finally.as_ref().is_some_and(|finally| finally.contains_yield())
+            }
+            _ => false,
+        }
+    }
+
+    // the name defined by a definition
+    pub fn defined_name(&self) -> Option<&str> {
+        match self {
+            Self::DefineVariable(name, ..) |
+            Self::DefineFunction(name, ..) |
+            Self::DefineStruct(name, ..) |
+            Self::DefineClass(name, ..) => Some(name),
+            _ => None,
         }
     }
 }
@@ -49,14 +213,22 @@ pub trait Format {
     fn format(&self, indent: usize) -> String;
 }
 
+// tuples with a single item need a trailing comma, like `(1,)`
+pub fn format_tuple(items: &[String]) -> String {
+    match items {
+        [item] => format!("({item},)"),
+        items => format!("({})", items.join(", ")),
+    }
+}
+
 impl Format for Block {
     fn format(&self, indent: usize) -> String {
-        const INDENT: &'static str = "    ";
+        const INDENT: &str = "    ";
 
         let indent_str = INDENT.repeat(indent);
-        let mut string = format!("{{\n");
+        let mut string = String::from("{\n");
 
-        for node in &self.statements {
+        for node in self.statements.iter() {
             match node {
                 Node::Block(block) => {
                     string += &(indent_str.clone() + INDENT + &block.format(indent + 1));
@@ -66,11 +238,11 @@ impl Format for Block {
                 }
             }
 
-            if string.chars().last().unwrap() != '}' {
-                string.push_str(";");
+            if !string.ends_with('}') {
+                string.push(';');
             }
 
-            string.push_str("\n");
+            string.push('\n');
         }
 
         string += &*format!("{indent_str}}}");
@@ -83,14 +255,58 @@ impl Format for Node {
         match self {
             Self::Statement(statement) => statement.format(indent),
             Self::Block(block) => block.format(indent),
-            Self::ParenArgs(root, args) => {
-                let args_fmt: Vec<_> = args.iter().map(|node| node.format(indent)).collect();
+            Self::ParenArgs(root, args, kwargs) => {
+                let args_fmt: Vec<_> = args.iter()
+                    .map(|node| node.format(indent))
+                    .chain(kwargs.iter().map(|(name, node)| format!("{name} = {}", node.format(indent))))
+                    .collect();
                 format!("{}({})", root.format(indent), args_fmt.join(", "))
             }
+            Self::Index(root, index) => format!("{}[{}]", root.format(indent), index.format(indent)),
+            Self::Attribute(root, name) => format!("{}.{name}", root.format(indent)),
+            Self::Slice(root, start, end) => {
+                let bound = |node: &Option<Box<Node>>| match node {
+                    Some(node) => node.format(indent),
+                    None => String::new(),
+                };
+                format!("{}[{}..{}]", root.format(indent), bound(start), bound(end))
+            }
+            Self::Function(params, block) => {
+                format!("fn({}) {}", params.format(indent), block.format(indent + 1))
+            }
+            Self::Match(value, arms) => {
+                let indent_str = "    ".repeat(indent + 1);
+                let arms: String = arms.iter()
+                    .map(|arm| {
+                        let guard = match &arm.guard {
+                            Some(guard) => format!(" if {}", guard.format(indent + 1)),
+                            None => String::new(),
+                        };
+                        format!("{indent_str}{}{guard} => {},\n",
+                            arm.pattern.format(indent + 1),
+                            arm.body.format(indent + 1))
+                    })
+                    .collect();
+                format!("match {} {{\n{arms}{}}}", value.format(indent), "    ".repeat(indent))
+            }
             Self::List(args) => {
                 let args_fmt: Vec<_> = args.iter().map(|node| node.format(indent)).collect();
                 format!("[{}]", args_fmt.join(", "))
             }
+            Self::Tuple(items) => {
+                let items: Vec<_> = items.iter().map(|node| node.format(indent)).collect();
+                format_tuple(&items)
+            }
+            Self::Map(entries) => {
+                let entries_fmt: Vec<_> = entries.iter()
+                    .map(|(key, value)| format!("{}: {}", key.format(indent), value.format(indent)))
+                    .collect();
+                format!("{{{}}}", entries_fmt.join(", "))
+            }
+            Self::UnaryOp(uop) => format!("({}{})",
+                uop.op.format(indent),
+                uop.value.format(indent),
+            ),
             Self::BinaryOp(bop) => format!("({} {} {})",
                 bop.a.format(indent),
                 bop.op.format(indent),
@@ -98,21 +314,88 @@ impl Format for Node {
             ),
             Self::Identifier(name) => format!("{name}"),
             Self::String(string) => format!("\"{string}\""),
+            Self::FormatString(parts) => {
+                let parts: String = parts.iter().map(|part| match part {
+                    Self::String(string) => string.replace('{', "{{").replace('}', "}}"),
+                    _ => format!("{{{}}}", part.format(indent)),
+                }).collect();
+                format!("f\"{parts}\"")
+            }
             Self::Integer(int) => format!("{int}"),
+            Self::Float(float) => format!("{float:?}"),
             Self::Boolean(boolean) => format!("{boolean}"),
         }
     }
 }
 
+impl Format for Params {
+    fn format(&self, indent: usize) -> String {
+        let param = |param: &Param| match &param.default {
+            Some(default) => format!("{} = {}", param.name, default.format(indent)),
+            None => param.name.to_string(),
+        };
+
+        let params: Vec<_> = self.positional.iter().map(param)
+            .chain(self.rest.iter().map(|rest| format!("*{rest}")))
+            .chain(self.keyword.iter().map(param))
+            .chain(self.options.iter().map(|options| format!("**{options}")))
+            .collect();
+        params.join(", ")
+    }
+}
+
+impl Format for Pattern {
+    fn format(&self, indent: usize) -> String {
+        match self {
+            Self::Wildcard => "_".to_string(),
+            Self::Binding(ident) => ident.to_string(),
+            Self::Literal(value) => value.format(indent),
+            Self::Range(start, end, inclusive) => {
+                format!("{start}..{}{end}", if *inclusive { "=" } else { "" })
+            }
+            Self::List(items) => {
+                let items: Vec<_> = items.iter().map(|item| item.format(indent)).collect();
+                format!("[{}]", items.join(", "))
+            }
+            Self::Tuple(items) => {
+                let items: Vec<_> = items.iter().map(|item| item.format(indent)).collect();
+                format_tuple(&items)
+            }
+            Self::Alternatives(alternatives) => {
+                let alternatives: Vec<_> = alternatives.iter()
+                    .map(|alternative| alternative.format(indent))
+                    .collect();
+                alternatives.join(" | ")
+            }
+        }
+    }
+}
+
 impl Format for Statement {
     fn format(&self, indent: usize) -> String {
         match self {
             Self::DefineVariable(ident, value) =>
                 format!("let {ident} = {}", value.format(indent)),
+            Self::Destructure(pattern, value) =>
+                format!("let {} = {}", pattern.format(indent), value.format(indent)),
             // Self::SetValue(ident, value) =>
             //     format!("{} = {}", ident, value.format(indent)),
-            Self::DefineFunction(ident, args, block) => {
-                format!("fn {ident}({}) {};", args.join(", "), block.format(0))
+            Self::DefineFunction(ident, args, block, doc) => {
+                let doc: String = doc.iter()
+                    .flat_map(|doc| doc.lines())
+                    .map(|line| format!("/// {line}\n{}", "    ".repeat(indent + 1)))
+                    .collect();
+                format!("{doc}fn {ident}({}) {};", args.format(indent), block.format(0))
+            }
+            Self::DefineStruct(ident, fields) => {
+                format!("struct {ident} {{ {} }}", fields.join(", "))
+            }
+            Self::DefineClass(ident, parent, block) => {
+                let parent = match parent {
+                    Some(parent) => format!("({parent})"),
+                    None => String::new(),
+                };
+                format!("class {ident}{parent} {};", block.format(indent + 1))
             }
             Self::If(condition, block, ext) => {
                 let mut fmt = format!("if {} {}", condition.format(indent), block.format(indent + 1));
@@ -122,13 +405,45 @@ impl Format for Statement {
                         Node::Block(..) => indent + 1, // else
                         _ => unreachable!()
                     };
-                    fmt.push_str(&*format!(" else {}", ext.format(indent)));
+                    fmt.push_str(&format!(" else {}", ext.format(indent)));
                 }
                 fmt
             }
-            Self::For(ident, sequence, block) => {
-                format!("for {ident} in {} {};", sequence.format(indent), block.format(indent + 1))
+            Self::For(pattern, sequence, block) => {
+                format!("for {} in {} {};",
+                    pattern.format(indent), sequence.format(indent), block.format(indent + 1))
+            }
+            Self::While(condition, block) => {
+                format!("while {} {};", condition.format(indent), block.format(indent + 1))
+            }
+            Self::Return(value) => match value {
+                Some(value) => format!("return {}", value.format(indent)),
+                None => "return".to_string(),
+            }
+            Self::Yield(value) => match value {
+                Some(value) => format!("yield {}", value.format(indent)),
+                None => "yield".to_string(),
+            }
+            Self::Import(path, name) => format!("import {path:?} as {name}"),
+            Self::ImportFrom(path, names) => format!("from {path:?} import {}", names.join(", ")),
+            Self::Export(statement) => format!("export {}", statement.format(indent)),
+            Self::Throw(value) => format!("throw {}", value.format(indent)),
+            Self::Try(block, catch, finally) => {
+                let mut fmt = format!("try {}", block.format(indent + 1));
+                if let Some((ident, handler)) = catch {
+                    let ident = match ident {
+                        Some(ident) => format!("{ident} "),
+                        None => String::new(),
+                    };
+                    fmt.push_str(&format!(" catch {ident}{}", handler.format(indent + 1)));
+                }
+                if let Some(finally) = finally {
+                    fmt.push_str(&format!(" finally {}", finally.format(indent + 1)));
+                }
+                fmt
             }
+            Self::Break => "break".to_string(),
+            Self::Continue => "continue".to_string(),
         }
     }
 }
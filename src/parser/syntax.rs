@@ -6,11 +6,25 @@ type Str = &'static str;
 // while const is used for individual tokens
 
 pub static DIGITS: Str = "0123456789";
-pub static LOWERCASE_LETTERS: Str = "abcdefghijklmnopqrstuvwxyz";
 pub static LETTERS: Str = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
 pub static IDENTIFIER_CHARS: Str = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789_";
 
 pub static STRING_TERMINATORS: Str = "\"'";
+// strings starting with this ignore escape sequences, like r"C:\path"
+pub const RAW_STRING_PREFIX: char = 'r';
+// strings starting with this have expressions in braces formatted into them
+pub const FORMAT_STRING_PREFIX: char = 'f';
+
+// separates statements on the same line, like `{ let t = f(); t * 2 }`
+pub const STATEMENT_SEPARATOR: Str = ";";
+
+// comments
+pub const LINE_COMMENT: Str = "//";
+pub const DOC_COMMENT: Str = "///";
+// four slashes or more is a regular comment, like in rust
+pub const NON_DOC_COMMENT: Str = "////";
+pub const BLOCK_COMMENT_START: Str = "/*";
+pub const BLOCK_COMMENT_END: Str = "*/";
 
 // keywords
 // only lowercase should be used
@@ -23,14 +37,33 @@ pub const KW_TRUE: Str = "true";
 pub const KW_FALSE: Str = "false";
 pub const KW_FOR: Str = "for";
 pub const KW_IN: Str = "in";
+pub const KW_RETURN: Str = "return";
+pub const KW_WHILE: Str = "while";
+pub const KW_BREAK: Str = "break";
+pub const KW_CONTINUE: Str = "continue";
+pub const KW_STRUCT: Str = "struct";
+pub const KW_CLASS: Str = "class";
+pub const KW_MATCH: Str = "match";
+pub const KW_THROW: Str = "throw";
+pub const KW_TRY: Str = "try";
+pub const KW_CATCH: Str = "catch";
+pub const KW_FINALLY: Str = "finally";
+pub const KW_IMPORT: Str = "import";
+pub const KW_FROM: Str = "from";
+pub const KW_AS: Str = "as";
+pub const KW_EXPORT: Str = "export";
+pub const KW_YIELD: Str = "yield";
 pub static KEYWORDS: &[&str] = &[
-    KW_LET, KW_IF, KW_ELIF, KW_ELSE, KW_FUNC, KW_TRUE, KW_FALSE, KW_FOR, KW_IN
+    KW_LET, KW_IF, KW_ELIF, KW_ELSE, KW_FUNC, KW_TRUE, KW_FALSE, KW_FOR, KW_IN,
+    KW_RETURN, KW_WHILE, KW_BREAK, KW_CONTINUE, KW_STRUCT, KW_CLASS,
+    KW_MATCH, KW_THROW, KW_TRY, KW_CATCH, KW_FINALLY, KW_IMPORT, KW_FROM,
+    KW_AS, KW_EXPORT, KW_YIELD,
 ];
 
 // NOTE useful for naming operators
 // https://doc.rust-lang.org/book/appendix-02-operators.html
 
-// NOTE some operators like () and . lack constants
+// NOTE some operators like () and [] lack constants
 
 pub const OP_ADD: Str = "+";
 pub const OP_SUB: Str = "-";
@@ -50,21 +83,45 @@ pub const OP_AND: Str = "&&";
 pub const OP_OR: Str = "||";
 
 pub const OP_SETVALUE: Str = "=";
+pub const OP_ADD_ASSIGN: Str = "+=";
+pub const OP_SUB_ASSIGN: Str = "-=";
+pub const OP_MUL_ASSIGN: Str = "*=";
+pub const OP_DIV_ASSIGN: Str = "/=";
+pub const OP_MOD_ASSIGN: Str = "%=";
 
 pub const OP_RANGE_EXCL: Str = "..";
-// pub const OP_RANGE_INCL = "..=";
+pub const OP_RANGE_INCL: Str = "..=";
+
+pub const OP_DOT: Str = ".";
+
+// function parameters
+pub const PARAM_REST: Str = "*";
+pub const PARAM_OPTIONS: Str = "**";
+
+// match expressions
+pub const MATCH_ARROW: Str = "=>";
+pub const PATTERN_WILDCARD: Str = "_";
+pub const PATTERN_OR: Str = "|";
+
+// unary operators
+pub const OP_NEG: Str = "-";
+pub const OP_NOT: Str = "!";
 
 // order of operations
 pub static OPERATOR_ORDER: &[&[Operator]] = &[
-    &[RangeExcl],
     &[Pow],
     &[Mul, Div, Mod],
     &[Add, Sub],
-    &[Equal, Inequal, Less, LessEqual, Greater, GreaterEqual],
+    &[RangeExcl, RangeIncl],
+    &[Equal, Inequal, Less, LessEqual, Greater, GreaterEqual, In],
     &[And],
     &[Or],
-    &[SetValue],
+    &[SetValue, AddAssign, SubAssign, MulAssign, DivAssign, ModAssign],
 ];
 
 // should not be used for checking the type of the following token
 pub static OPERATOR_CHARS: Str = "+-*/^%.!=<>&|";
+
+// a value starting with one of these is a unary operation,
+// which binds tighter than all of the operators above
+pub static UNARY_OPERATOR_CHARS: Str = "-!";
@@ -12,6 +12,22 @@ pub enum Keyword {
     False,
     For,
     In,
+    Return,
+    While,
+    Break,
+    Continue,
+    Struct,
+    Class,
+    Match,
+    Throw,
+    Try,
+    Catch,
+    Finally,
+    Import,
+    From,
+    As,
+    Export,
+    Yield,
 }
 
 #[derive(Debug, PartialEq, Clone, Copy)]
@@ -31,6 +47,8 @@ pub enum Operator {
     LessEqual,
     Greater,
     GreaterEqual,
+    // membership, like `x in list`
+    In,
 
     // logic
     And,
@@ -38,14 +56,54 @@ pub enum Operator {
 
     // ranges
     RangeExcl,
+    RangeIncl,
 
-    // misc
+    // assignment
     SetValue,
+    AddAssign,
+    SubAssign,
+    MulAssign,
+    DivAssign,
+    ModAssign,
+}
+
+impl Operator {
+    pub fn is_assignment(&self) -> bool {
+        matches!(self, Self::SetValue | Self::AddAssign | Self::SubAssign
+            | Self::MulAssign | Self::DivAssign | Self::ModAssign)
+    }
+
+    // the operator that a compound assignment applies, like `+` for `+=`
+    pub fn compound(&self) -> Option<Operator> {
+        match self {
+            Self::AddAssign => Some(Self::Add),
+            Self::SubAssign => Some(Self::Sub),
+            Self::MulAssign => Some(Self::Mul),
+            Self::DivAssign => Some(Self::Div),
+            Self::ModAssign => Some(Self::Mod),
+            _ => None
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum UnaryOperator {
+    Neg,
+    Not,
+}
+
+impl Format for UnaryOperator {
+    fn format(&self, _indent: usize) -> String {
+        match self {
+            Self::Neg => OP_NEG,
+            Self::Not => OP_NOT,
+        }.to_string()
+    }
 }
 
 impl Format for Operator {
     fn format(&self, _indent: usize) -> String {
-        format!("{}", match self {
+        match self {
             Self::Add => OP_ADD,
             Self::Sub => OP_SUB,
             Self::Mul => OP_MUL,
@@ -58,10 +116,17 @@ impl Format for Operator {
             Self::LessEqual => OP_LESSEQUAL,
             Self::Greater => OP_GREATER,
             Self::GreaterEqual => OP_GREATEREQUAL,
+            Self::In => KW_IN,
             Self::And => OP_AND,
             Self::Or => OP_OR,
             Self::RangeExcl => OP_RANGE_EXCL,
+            Self::RangeIncl => OP_RANGE_INCL,
             Self::SetValue => OP_SETVALUE,
-        })
+            Self::AddAssign => OP_ADD_ASSIGN,
+            Self::SubAssign => OP_SUB_ASSIGN,
+            Self::MulAssign => OP_MUL_ASSIGN,
+            Self::DivAssign => OP_DIV_ASSIGN,
+            Self::ModAssign => OP_MOD_ASSIGN,
+        }.to_string()
     }
 }
@@ -1,8 +1,51 @@
 use crate::*;
 use crate::parser::node::*;
+use crate::parser::tokens::{Operator, UnaryOperator};
 use std::collections::HashMap;
+use std::cmp::Ordering;
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::fmt;
 
-type IntegerType = i32;
+mod map;
+pub use map::*;
+
+mod index;
+
+mod range;
+pub use range::*;
+
+mod structs;
+pub use structs::*;
+
+mod class;
+pub use class::*;
+
+mod pattern;
+
+mod exception;
+pub use exception::*;
+
+mod module;
+pub use module::*;
+
+mod params;
+
+mod iter;
+pub use iter::*;
+
+mod generator;
+pub use generator::*;
+
+mod compare;
+
+mod gc;
+pub use gc::*;
+
+pub type IntegerType = i32;
+pub type FloatType = f64;
+// evaluated keyword arguments
+pub type Kwargs = Vec<(Box<str>, Object)>;
 
 // TODO replace some 'name' with 'ident'
 
@@ -11,18 +54,122 @@ use quick_error::quick_error;
 quick_error! {
     #[derive(Debug)]
     pub enum RuntimeError {
-        ExpectedType(typ: Type) {}
-        ExpectedArgs(len: usize) {}
-        NameError(name: Box<str>) {}
+        ExpectedType(typ: Type) {
+            display("expected a value of type {:?}", typ)
+        }
+        ExpectedArgs(len: usize) {
+            display("expected {} arguments", len)
+        }
+        NameError(name: Box<str>) {
+            display("name '{}' is not defined", name)
+        }
+        DivisionByZero {
+            display("division by zero")
+        }
+        IntegerOverflow {
+            display("integer overflow")
+        }
+        InvalidKey(typ: Type) {
+            display("a value of type {:?} can't be used as a key", typ)
+        }
+        KeyError(key: Key) {
+            display("key {} not found", key)
+        }
+        IndexError(index: IntegerType) {
+            display("index {} out of range", index)
+        }
+        ImmutableType(typ: Type) {
+            display("a value of type {:?} can't be changed", typ)
+        }
+        AttributeError(typ: Type, name: Box<str>) {
+            display("a value of type {:?} has no attribute '{}'", typ, name)
+        }
+        MissingArgument(name: Box<str>) {
+            display("missing argument '{}'", name)
+        }
+        UnknownArgument(name: Box<str>) {
+            display("unknown keyword argument '{}'", name)
+        }
+        DuplicateArgument(name: Box<str>) {
+            display("argument '{}' given twice", name)
+        }
+        InvalidStep(step: IntegerType) {
+            display("invalid step {}", step)
+        }
+        Unordered(a: Type, b: Type) {
+            display("values of type {:?} and {:?} can't be ordered", a, b)
+        }
+        // the value is shown as it would be in a collection
+        NoMatch(value: String) {
+            display("no pattern matches {}", value)
+        }
+        InvalidControlFlow(flow: ControlFlow) {
+            display("{}", flow)
+        }
+        ImportError(path: Box<str>, reason: String) {
+            display("could not import {}: {}", path, reason)
+        }
+        CyclicImport(path: Box<str>) {
+            display("cyclic import of {}", path)
+        }
+        // a command run by `call` that couldn't be started or exited with an error
+        CommandError(cmd: String, reason: String) {
+            display("command '{}' failed: {}", cmd, reason)
+        }
+        InvalidYield {
+            display("yield outside of generator")
+        }
+        IteratorRunning {
+            display("the iterator is already running")
+        }
+        StopIteration {
+            display("the iterator is exhausted")
+        }
+        // an error thrown by a script
+        Exception(value: Object) {
+            display("{}", exception_message(value))
+        }
     }
 }
 
 use RuntimeError::*;
 
+// control flow is propagated as an error, so that it can unwind through
+// nested blocks, and is caught where it belongs (e.g. at the function call)
+quick_error! {
+    #[derive(Debug)]
+    pub enum ControlFlow {
+        Return(value: Object) {
+            display("return outside of function")
+        }
+        Break {
+            display("break outside of loop")
+        }
+        Continue {
+            display("continue outside of loop")
+        }
+    }
+}
+
+// evaluates the body of a loop, returning whether the loop should go on
+fn eval_loop_body(block: &Node, runtime: &mut Runtime, scope: &mut Scope) -> Result<bool> {
+    match block.eval(runtime, scope) {
+        Ok(_) => Ok(true),
+        Err(err) => match err.downcast::<ControlFlow>() {
+            Ok(flow) => match *flow {
+                ControlFlow::Break => Ok(false),
+                ControlFlow::Continue => Ok(true),
+                flow => Err(flow.into()),
+            }
+            Err(err) => Err(err),
+        }
+    }
+}
+
 #[macro_export]
 macro_rules! expect_type {
     ($value:expr, $type:ident) => {{
-        use crate::runtime::{Object, Type, RuntimeError};
+        use $crate::runtime::{Object, Type, RuntimeError};
         match $value {
             Object::$type(value) => value,
             _ => { return Err( RuntimeError::ExpectedType(Type::$type).into()); }
@@ -32,29 +179,58 @@ macro_rules! expect_type {
 
 pub struct Runtime {
     pub globals: Scope,
+    // the methods of the builtin types
+    pub methods: HashMap<Type, Scope>,
+    // imported modules by their canonical path,
+    // which are None while they are being evaluated
+    pub modules: HashMap<std::path::PathBuf, Option<Rc<Module>>>,
+    pub collector: Collector,
+    // generators dropped before they finished, which are closed after the current statement
+    pub closing: Rc<RefCell<Vec<Generator>>>,
 }
 
-#[derive(Debug, Clone)]
-pub struct Scope {
+// scopes are reference counted, so that functions can keep
+// the scope they were defined in alive after it has been left
+#[derive(Clone)]
+pub struct Scope(Rc<RefCell<ScopeData>>);
+
+pub struct ScopeData {
     pub objects: Vec<Object>,
     pub names: HashMap<Box<str>, usize>,
-    pub parent: Option<*mut Scope>,
+    pub parent: Option<Scope>,
 }
 
 #[derive(Debug, Clone)]
 pub enum Function {
     Pointer(fn(&mut Runtime, &mut Scope) -> Result<Object>),
-    Block(Block),
+    // the scope is the one the function was defined in, and the block is
+    // shared by the copies of the function, which makes them equal
+    Block(Rc<Block>, Scope),
+    // a function containing `yield`, which returns an iterator when called
+    Generator(Rc<Block>, Scope),
+    // a method together with the object it was accessed on,
+    // which is passed as the first argument
+    Bound(Object, Object),
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Type {
-    // Null,
+    Null,
     String,
     Integer,
+    Float,
     Boolean,
     Function,
     List,
+    Map,
+    Range,
+    StructType,
+    Struct,
+    Class,
+    Instance,
+    Module,
+    Tuple,
+    Iterator,
 }
 
 #[derive(Debug, Clone)]
@@ -62,85 +238,365 @@ pub enum Object {
     Null,
     String(String),
     Integer(IntegerType),
+    Float(FloatType),
     Boolean(bool),
     Function {
         func: Box<Function>,
-        args: Vec<Box<str>>,
+        args: Params,
     },
-    List(Vec<Object>),
+    // lists and maps are shared when assigned, as in python
+    List(Rc<RefCell<Vec<Object>>>),
+    Map(Rc<RefCell<Map>>),
+    // tuples can't be changed, so they don't need a RefCell
+    Tuple(Rc<[Object]>),
+    Range(Range),
+    StructType(Rc<StructType>),
+    // instances of structs are shared, like lists and maps
+    Struct(Rc<StructType>, Rc<RefCell<Vec<Object>>>),
+    Class(Rc<Class>),
+    // the fields of an instance are set freely, usually in `init`
+    Instance(Rc<Class>, Rc<RefCell<Map>>),
+    Module(Rc<Module>),
+    Iterator(Iter),
 }
 
 impl Runtime {
     pub fn new() -> Self {
         Self {
             globals: Scope::new(None),
+            methods: HashMap::new(),
+            modules: HashMap::new(),
+            collector: Collector::default(),
+            closing: Rc::default(),
         }
     }
+
+    // the scope holding the methods of a type
+    pub fn methods(&mut self, typ: Type) -> &mut Scope {
+        self.methods.entry(typ).or_insert_with(|| Scope::new(None))
+    }
+}
+
+impl Function {
+    // a function defined in a script, which is a generator if it contains `yield`
+    pub fn new(runtime: &mut Runtime, block: &Block, scope: &Scope) -> Self {
+        runtime.track(scope);
+        if block.contains_yield() {
+            Self::Generator(Rc::new(block.clone()), scope.clone())
+        } else {
+            Self::Block(Rc::new(block.clone()), scope.clone())
+        }
+    }
+}
+
+impl Default for Runtime {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Runtime {
+    // calls a function object with already evaluated arguments
+    pub fn call(&mut self, scope: &Scope, func: &Object, args: Vec<Object>) -> Result<Object> {
+        self.call_with(scope, func, args, Vec::new())
+    }
+
+    // like call, but with keyword arguments,
+    // native functions get the calling scope as their parent
+    pub fn call_with(
+        &mut self,
+        scope: &Scope,
+        func: &Object,
+        args: Vec<Object>,
+        kwargs: Kwargs,
+    ) -> Result<Object> {
+        let (func, params) = match func {
+            Object::Function { func, args } => (func, args),
+            Object::StructType(typ) => return typ.instantiate(self, args, kwargs),
+            Object::Class(class) => return self.instantiate(scope, class, args, kwargs),
+            _ => return Err(ExpectedType(Type::Function).into())
+        };
+
+        if let Function::Bound(receiver, method) = &**func {
+            let mut args = args;
+            args.insert(0, receiver.clone());
+            return self.call_with(scope, method, args, kwargs);
+        }
+
+        match &**func {
+            Function::Pointer(ptr) => {
+                let mut func_scope = Scope::new(Some(scope.clone()));
+                self.bind_args(params, &mut func_scope, args, kwargs)?;
+                ptr(self, &mut func_scope)
+            }
+            Function::Block(block, parent) => {
+                let mut func_scope = Scope::new(Some(parent.clone()));
+                self.bind_args(params, &mut func_scope, args, kwargs)?;
+
+                let result = match block.eval(self, &mut func_scope) {
+                    Err(err) => match err.downcast::<ControlFlow>() {
+                        Ok(flow) => match *flow {
+                            ControlFlow::Return(value) => Ok(value),
+                            // break and continue may not leave the function
+                            flow => Err(InvalidControlFlow(flow).into()),
+                        }
+                        Err(err) => Err(err),
+                    }
+                    result => result,
+                };
+
+                // generators left in the function are closed before it returns
+                drop(func_scope);
+                self.close_dropped()?;
+                result
+            }
+            Function::Generator(block, parent) => {
+                let mut func_scope = Scope::new(Some(parent.clone()));
+                self.bind_args(params, &mut func_scope, args, kwargs)?;
+                Ok(Object::Iterator(Iter::new(Generator::new(self, Block::clone(block), func_scope))))
+            }
+            Function::Bound(..) => unreachable!()
+        }
+    }
+
+    // gets an attribute of an object, which is either a field of a struct
+    // or an instance, a value in a map, a name exported by a module,
+    // or a method bound to the object
+    pub fn attribute(&self, object: Object, name: &str) -> Result<Object> {
+        if let Some(value) = object.field(name) {
+            return Ok(value);
+        }
+
+        if let Object::Map(map) = &object
+            && let Some(value) = map.borrow().get(&Key::String(name.to_string()))
+        {
+            return Ok(value.clone());
+        }
+
+        if let Object::Module(module) = &object {
+            return module.get(name)
+                .ok_or(AttributeError(object.typ(), name.into()).into());
+        }
+
+        let method = match &object {
+            Object::Instance(class, _) => class.method(name),
+            // methods accessed on the class itself are left unbound,
+            // so that `Parent.method(self)` can call an overridden method
+            Object::Class(class) => return class.method(name)
+                .ok_or(AttributeError(object.typ(), name.into()).into()),
+            _ => self.methods.get(&object.typ())
+                .and_then(|methods| methods.get_local(name)),
+        };
+
+        match method {
+            Some(method) => Ok(bind(object, method)),
+            None => Err(AttributeError(object.typ(), name.into()).into())
+        }
+    }
+}
+
+impl Object {
+    pub fn list(items: Vec<Object>) -> Self {
+        Self::List(Rc::new(RefCell::new(items)))
+    }
+
+    pub fn map(map: Map) -> Self {
+        Self::Map(Rc::new(RefCell::new(map)))
+    }
+
+    pub fn tuple(items: Vec<Object>) -> Self {
+        Self::Tuple(items.into())
+    }
+
+    // the items of a list or a tuple
+    pub fn items(&self) -> Option<Vec<Object>> {
+        match self {
+            Self::List(list) => Some(list.borrow().clone()),
+            Self::Tuple(items) => Some(items.to_vec()),
+            _ => None,
+        }
+    }
+
+    // the name of the type, which is the declared name for structs
+    pub fn type_name(&self) -> String {
+        match self {
+            Self::Struct(typ, _) => typ.name.to_string(),
+            Self::Instance(class, _) => class.name.to_string(),
+            _ => format!("{:?}", self.typ()),
+        }
+    }
+
+    pub fn typ(&self) -> Type {
+        match self {
+            Self::Null => Type::Null,
+            Self::String(_) => Type::String,
+            Self::Integer(_) => Type::Integer,
+            Self::Float(_) => Type::Float,
+            Self::Boolean(_) => Type::Boolean,
+            Self::Function { .. } => Type::Function,
+            Self::List(_) => Type::List,
+            Self::Map(_) => Type::Map,
+            Self::Tuple(_) => Type::Tuple,
+            Self::Iterator(_) => Type::Iterator,
+            Self::Range(_) => Type::Range,
+            Self::StructType(_) => Type::StructType,
+            Self::Struct(..) => Type::Struct,
+            Self::Class(_) => Type::Class,
+            Self::Instance(..) => Type::Instance,
+            Self::Module(_) => Type::Module,
+        }
+    }
+
+    // whether the value counts as true in conditions, where null, zero
+    // and empty collections are false, like in python
+    pub fn truthy(&self) -> bool {
+        match self {
+            Self::Null => false,
+            Self::Boolean(boolean) => *boolean,
+            Self::Integer(integer) => *integer != 0,
+            Self::Float(float) => *float != 0.0,
+            Self::String(string) => !string.is_empty(),
+            Self::List(list) => !list.borrow().is_empty(),
+            Self::Map(map) => !map.borrow().is_empty(),
+            Self::Tuple(items) => !items.is_empty(),
+            Self::Range(range) => !range.is_empty(),
+            _ => true,
+        }
+    }
+
+    // like the display format, but with strings quoted,
+    // which is used for values inside of collections
+    pub fn repr(&self) -> String {
+        match self {
+            Self::String(string) => format!("{string:?}"),
+            _ => self.to_string()
+        }
+    }
+}
+
+impl fmt::Display for Object {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Null => write!(f, "null"),
+            Self::String(string) => write!(f, "{string}"),
+            Self::Integer(integer) => write!(f, "{integer}"),
+            // debug formatting keeps the decimal point of whole floats
+            Self::Float(float) => write!(f, "{float:?}"),
+            Self::Boolean(boolean) => write!(f, "{boolean}"),
+            Self::Function { args, .. } => write!(f, "<fn({})>", args.format(0)),
+            Self::List(list) => {
+                let items: Vec<_> = list.borrow().iter().map(Object::repr).collect();
+                write!(f, "[{}]", items.join(", "))
+            }
+            Self::Tuple(items) => {
+                let items: Vec<_> = items.iter().map(Object::repr).collect();
+                write!(f, "{}", format_tuple(&items))
+            }
+            Self::StructType(typ) => write!(f, "<struct {}>", typ.name),
+            Self::Struct(typ, values) => {
+                let fields = typ.fields.iter().zip(values.borrow().iter())
+                    .map(|(name, value)| format!("{name}: {}", value.repr()))
+                    .collect();
+                write_fields(f, &typ.name, fields)
+            }
+            Self::Class(class) => write!(f, "<class {}>", class.name),
+            Self::Module(module) => write!(f, "<module {:?}>", module.path),
+            Self::Iterator(_) => write!(f, "<iterator>"),
+            Self::Instance(class, fields) => {
+                // field names are always strings, which are shown unquoted
+                let fields = fields.borrow().entries.iter()
+                    .map(|(key, value)| format!("{}: {}", Object::from(key.clone()), value.repr()))
+                    .collect();
+                write_fields(f, &class.name, fields)
+            }
+            // ranges are shown as the list they represent
+            Self::Range(range) => write!(f, "{}", range.to_list()),
+            Self::Map(map) => {
+                let items: Vec<_> = map.borrow().entries.iter()
+                    .map(|(key, value)| format!("{key}: {}", value.repr()))
+                    .collect();
+                write!(f, "{{{}}}", items.join(", "))
+            }
+        }
+    }
+}
+
+// shows a struct or an instance as `Name { field: value }`
+fn write_fields(f: &mut fmt::Formatter, name: &str, fields: Vec<String>) -> fmt::Result {
+    if fields.is_empty() {
+        write!(f, "{name} {{}}")
+    } else {
+        write!(f, "{name} {{ {} }}", fields.join(", "))
+    }
 }
 
 impl Scope {
-    pub fn new(parent: Option<*mut Scope>) -> Self {
-        Self {
+    pub fn new(parent: Option<Scope>) -> Self {
+        Self(Rc::new(RefCell::new(ScopeData {
             names: HashMap::new(),
             objects: Vec::new(),
             parent,
-        }
+        })))
+    }
+
+    pub fn parent(&self) -> Option<Scope> {
+        self.0.borrow().parent.clone()
     }
 
-    fn add_object(&mut self, object: Object) -> usize {
-        self.objects.push(object);
-        self.objects.len() - 1
+    // defining a name that already exists in this scope replaces it
+    pub fn define(&self, name: &str, object: Object) {
+        let mut data = self.0.borrow_mut();
+        if let Some(id) = data.names.get(name) {
+            let id = *id;
+            data.objects[id] = object;
+        } else {
+            data.objects.push(object);
+            let id = data.objects.len() - 1;
+            data.names.insert(name.into(), id);
+        }
     }
 
-    // TODO remove runtime?
-    pub fn define(&mut self, name: &str, object: Object) {
-        assert!(!self.names.contains_key(name)); // TODO fix
-        let id = self.add_object(object);
-        self.names.insert(name.into(), id);
+    // gets a name from this scope only, without looking in the parents
+    pub fn get_local(&self, name: &str) -> Option<Object> {
+        let data = self.0.borrow();
+        data.names.get(name).map(|id| data.objects[*id].clone())
     }
 
-    pub fn update(&mut self, runtime: &mut Runtime, name: &str, object: Object) -> Result<()> {
-        if let Some(id) = self.names.get(name) {
-            self.objects.insert(*id, object);
+    pub fn update(&self, name: &str, object: Object) -> Result<()> {
+        let mut data = self.0.borrow_mut();
+        if let Some(id) = data.names.get(name) {
+            let id = *id;
+            data.objects[id] = object;
             Ok(())
 
-        } else if let Some(parent) = self.parent {
-            unsafe {
-                (*parent).update(runtime, name, object)
-            }
+        } else if let Some(parent) = &data.parent {
+            parent.update(name, object)
 
         } else {
             Err(NameError(name.into()).into())
         }
     }
 
-    pub fn get(&mut self, runtime: &Runtime, name: &str) -> Result<Object> {
-        if let Some(id) = self.names.get(name) {
-            Ok(self.objects[*id].clone())
+    pub fn get(&self, runtime: &Runtime, name: &str) -> Result<Object> {
+        let data = self.0.borrow();
+        if let Some(id) = data.names.get(name) {
+            Ok(data.objects[*id].clone())
+        } else if let Some(parent) = &data.parent {
+            parent.get(runtime, name)
+        } else if !Rc::ptr_eq(&self.0, &runtime.globals.0) {
+            runtime.globals.get(runtime, name)
         } else {
-            if let Some(parent) = self.parent {
-                unsafe {
-                    (*parent).get(runtime, name)
-                }
-            } else {
-                match runtime.globals.names.get(name) {
-                    Some(id) => {
-                        Ok(runtime.globals.objects[*id].clone())
-                    }
-                    None => Err(NameError(name.into()).into())
-                }
-            }
+            Err(NameError(name.into()).into())
         }
     }
+}
 
-    fn root(&mut self) -> *mut Self {
-        if let Some(parent) = self.parent {
-            unsafe {
-                (*parent).root()
-            }
-        } else {
-            self
-        }
+// scopes may contain functions referring back to the scope itself,
+// so only the names are printed
+impl std::fmt::Debug for Scope {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let data = self.0.borrow();
+        f.debug_set().entries(data.names.keys()).finish()
     }
 }
 
@@ -155,60 +611,102 @@ pub trait Evaluate {
 impl Evaluate for Node {
     fn eval(&self, runtime: &mut Runtime, scope: &mut Scope) -> Result<Object> {
         match self {
-            Self::ParenArgs(root, args) => {
-                match root.eval(runtime, scope)? {
-                    Object::Function { func, args: arg_names } => {
-                        if args.len() != arg_names.len() {
-                            return Err(ExpectedArgs(arg_names.len()).into());
-                        }
+            Self::ParenArgs(root, args, kwargs) => {
+                let func = root.eval(runtime, scope)?;
+                let args: Result<Vec<Object>> = args.iter()
+                    .map(|n| n.eval(runtime, scope)).collect();
+                let kwargs: Result<Kwargs> = kwargs.iter()
+                    .map(|(name, n)| Ok((name.clone(), n.eval(runtime, scope)?))).collect();
+                runtime.call_with(scope, &func, args?, kwargs?)
+            }
 
-                        let mut func_scope = Scope::new(Some(scope.root()));
-                        for (i, arg_name) in arg_names.iter().enumerate() {
-                            let arg_node: &Node = &args[i];
-                            let arg = arg_node.eval(runtime, scope)?;
-                            func_scope.define(arg_name, arg);
-                        }
+            Self::Function(args, block) => Ok(Object::Function {
+                func: Box::new(Function::new(runtime, block, scope)),
+                args: args.clone(),
+            }),
 
-                        match *func {
-                            Function::Pointer(ptr) => {
-                                ptr(runtime, &mut func_scope)
-                            }
-                            Function::Block(block) => {
-                                block.eval(runtime, &mut func_scope)
-                            }
-                        }
-                    }
-                    _ => Err(ExpectedType(Type::Function).into())
-                }
+            Self::Match(value, arms) => {
+                let value = value.eval(runtime, scope)?;
+                let (arm, mut scope) = runtime.match_arm(scope, &value, arms)?;
+                arm.body.eval(runtime, &mut scope)
             }
 
             Self::Statement(node) => node.eval(runtime, scope), 
+            Self::UnaryOp(node) => node.eval(runtime, scope),
             Self::BinaryOp(node) => node.eval(runtime, scope), 
 
             Self::Block(node) => {
-                node.eval(runtime, &mut scope.clone())
+                node.eval(runtime, &mut Scope::new(Some(scope.clone())))
+            }
+
+            Self::Index(target, index) => {
+                let target = target.eval(runtime, scope)?;
+                target.index(index.eval(runtime, scope)?)
+            }
+            Self::Slice(target, start, end) => {
+                let target = target.eval(runtime, scope)?;
+                let start = match start {
+                    Some(start) => Some(expect_type!(start.eval(runtime, scope)?, Integer)),
+                    None => None,
+                };
+                let end = match end {
+                    Some(end) => Some(expect_type!(end.eval(runtime, scope)?, Integer)),
+                    None => None,
+                };
+                target.slice(start, end)
+            }
+
+            Self::Attribute(target, name) => {
+                let target = target.eval(runtime, scope)?;
+                runtime.attribute(target, name)
             }
 
             Self::Identifier(ident) => scope.get(runtime, ident),
             Self::String(string) => Ok(Object::String(string.to_string())),
+            Self::FormatString(parts) => {
+                let mut string = String::new();
+                for part in parts {
+                    string += &part.eval(runtime, scope)?.to_string();
+                }
+                Ok(Object::String(string))
+            }
             Self::Integer(integer) => Ok(Object::Integer(*integer)),
+            Self::Float(float) => Ok(Object::Float(*float)),
             Self::Boolean(boolean) => Ok(Object::Boolean(*boolean)),
             Self::List(list) => {
                 let result: Result<Vec<Object>> = list.iter()
                     .map(|n| n.eval(runtime, scope)).collect();
-                Ok(Object::List(result?))
+                Ok(Object::list(result?))
+            }
+            Self::Tuple(items) => {
+                let result: Result<Vec<Object>> = items.iter()
+                    .map(|n| n.eval(runtime, scope)).collect();
+                Ok(Object::tuple(result?))
+            }
+            Self::Map(entries) => {
+                let mut map = Map::new();
+                for (key, value) in entries {
+                    let key = key.eval(runtime, scope)?.try_into()?;
+                    map.insert(key, value.eval(runtime, scope)?);
+                }
+                Ok(Object::map(map))
             }
         }
     }
 }
 
 impl Evaluate for Block {
+    // the value of a block is the value of its last statement, like in rust,
+    // which is also what functions without a `return` return
     fn eval(&self, runtime: &mut Runtime, scope: &mut Scope) -> Result<Object> {
-        for statement in &self.statements {
-            let _ = statement.eval(runtime, scope)?;
+        let mut value = Object::Null;
+        for statement in self.statements.iter() {
+            value = statement.eval(runtime, scope)?;
+            // generators dropped by the statement run their finally blocks
+            runtime.close_dropped()?;
         }
 
-        Ok(Object::Null)
+        Ok(value)
     }
 }
 
@@ -220,53 +718,145 @@ impl Evaluate for Statement {
                 scope.define(name, value);
                 Ok(Object::Null)
             }
-            Self::DefineFunction(name, args, block) => {
+            Self::Destructure(pattern, value) => {
+                let value = value.eval(runtime, scope)?;
+                if !pattern.matches(&value, scope) {
+                    return Err(NoMatch(value.repr()).into());
+                }
+                Ok(Object::Null)
+            }
+            Self::DefineFunction(name, args, block, _) => {
                 // TODO replace cloning with pointer or something?
                 scope.define(name, Object::Function {
-                    func: Box::new(Function::Block(block.clone())),
+                    func: Box::new(Function::new(runtime, block, scope)),
                     args: args.clone(),
                 });
                 Ok(Object::Null)
             }
+            Self::DefineStruct(name, fields) => {
+                scope.define(name, Object::StructType(Rc::new(StructType {
+                    name: name.as_str().into(),
+                    fields: fields.clone(),
+                })));
+                Ok(Object::Null)
+            }
+            Self::DefineClass(name, parent, block) => {
+                let parent = match parent {
+                    Some(parent) => Some(expect_type!(scope.get(runtime, parent)?, Class)),
+                    None => None,
+                };
+                // the methods are defined in a scope of their own
+                let mut methods = Scope::new(Some(scope.clone()));
+                // the class refers back to the scope it's defined in through its methods
+                runtime.track(&methods);
+                block.eval(runtime, &mut methods)?;
+                scope.define(name, Object::Class(Rc::new(Class {
+                    name: name.as_str().into(),
+                    parent,
+                    methods,
+                })));
+                Ok(Object::Null)
+            }
             Self::If(condition, block, ext) => {
-                if expect_type!(condition.eval(runtime, scope)?, Boolean) {
-                    block.eval(runtime, scope)?;
-                    if let Some(ext) = ext {
-                        match &**ext {
-                            // else statements:
-                            Node::Block(ext_block) => {
-                                ext_block.eval(runtime, scope)?;
-                            }
-                            // elif statements:
-                            Node::Statement(ext_statement) => {
-                                match ext_statement {
-                                    Statement::If(..) => {
-                                        ext_statement.eval(runtime, scope)?;
-                                    }
-                                    _ => unreachable!()
-                                }
-                            }
-                            _ => unreachable!()
-                        };
+                if condition.eval(runtime, scope)?.truthy() {
+                    block.eval(runtime, scope)
+                } else if let Some(ext) = ext {
+                    // either an else block, or an elif statement
+                    ext.eval(runtime, scope)
+                } else {
+                    Ok(Object::Null)
+                }
+            }
+            Self::For(pattern, sequence, block) => {
+                let sequence = sequence.eval(runtime, scope)?;
+                let iter = runtime.iter(scope, sequence)?;
+                while let Some(object) = iter.next(runtime)? {
+                    // TODO reuse scope instead
+                    let mut scope = Scope::new(Some(scope.clone()));
+                    if !pattern.matches(&object, &scope) {
+                        return Err(NoMatch(object.repr()).into());
+                    }
+                    if !eval_loop_body(block, runtime, &mut scope)? {
+                        break
                     }
                 }
-
                 Ok(Object::Null)
             }
-            Self::For(ident, sequence, block) => {
-                let sequence = expect_type!(sequence.eval(runtime, scope)?, List);
-                for object in sequence.iter() {
-                    // TODO reuse scope instead
-                    let mut scope = Scope::new(Some(scope));
-                    scope.define(ident, object.clone());
-                    block.eval(runtime, &mut scope)?;
+            Self::While(condition, block) => {
+                while condition.eval(runtime, scope)?.truthy() {
+                    if !eval_loop_body(block, runtime, scope)? {
+                        break
+                    }
+                }
+                Ok(Object::Null)
+            }
+            Self::Return(value) => {
+                let value = match value {
+                    Some(value) => value.eval(runtime, scope)?,
+                    None => Object::Null,
+                };
+                Err(ControlFlow::Return(value).into())
+            }
+            Self::Import(path, name) => {
+                let module = runtime.import(path)?;
+                scope.define(name, Object::Module(module));
+                Ok(Object::Null)
+            }
+            Self::ImportFrom(path, names) => {
+                let module = runtime.import(path)?;
+                for name in names {
+                    let value = module.get(name)
+                        .ok_or(AttributeError(Type::Module, name.clone()))?;
+                    scope.define(name, value);
                 }
                 Ok(Object::Null)
             }
+            // which names are exported is known before the module is evaluated
+            Self::Export(statement) => statement.eval(runtime, scope),
+            Self::Throw(value) => Err(exception(value.eval(runtime, scope)?).into()),
+            Self::Try(block, catch, finally) => {
+                let result = match block.eval(runtime, scope) {
+                    // control flow isn't an error, so it passes through
+                    Err(err) if !err.is::<ControlFlow>() && let Some((ident, handler)) = catch => {
+                        let mut scope = Scope::new(Some(scope.clone()));
+                        if let Some(ident) = ident {
+                            scope.define(ident, caught_error(err));
+                        }
+                        handler.eval(runtime, &mut scope)
+                    }
+                    result => result,
+                };
+
+                // runs whether the block succeeded, failed or was left early
+                if let Some(finally) = finally {
+                    finally.eval(runtime, scope)?;
+                }
+
+                result
+            }
+            // functions containing yield are run by the generator instead
+            Self::Yield(_) => Err(InvalidYield.into()),
+            Self::Break => Err(ControlFlow::Break.into()),
+            Self::Continue => Err(ControlFlow::Continue.into()),
         }
     }
 }
 
+impl Evaluate for UnaryOp {
+    fn eval(&self, runtime: &mut Runtime, scope: &mut Scope) -> Result<Object> {
+        let value = self.value.eval(runtime, scope)?;
+
+        Ok(match self.op {
+            UnaryOperator::Neg => match value {
+                Object::Integer(integer) => Object::Integer(integer.checked_neg().ok_or(IntegerOverflow)?),
+                Object::Float(float) => Object::Float(-float),
+                _ => return Err(ExpectedType(Type::Integer).into())
+            }
+            UnaryOperator::Not => Object::Boolean(!value.truthy()),
+        })
+    }
+}
+
 impl Evaluate for BinaryOp {
     fn eval(&self, runtime: &mut Runtime, scope: &mut Scope) -> Result<Object> {
         use crate::parser::tokens::Operator::*;
@@ -276,75 +866,161 @@ impl Evaluate for BinaryOp {
             Equal | Inequal | Less | LessEqual | Greater | GreaterEqual => {
                 let a = self.a.eval(runtime, scope)?;
                 let b = self.b.eval(runtime, scope)?;
+                apply_operator(self.op, a, b)?
+            }
 
-                match self.op {
-                    Add => {
-                        // string concatenation
-                        match a {
-                            Object::String(a) => {
-                                let b = expect_type!(b, String);
-                                return Ok(Object::String(a + &b))
-                            }
-                            _ => {}
-                        }
-                    }
-                    _ => {}
-                }
-
-                let a = expect_type!(a, Integer);
-                let b = expect_type!(b, Integer);
-
-                match self.op {
-                    Add | Sub | Mul | Div | Pow | Mod => {
-                        Object::Integer(match self.op {
-                            Add => a + b,
-                            Sub => a - b,
-                            Mul => a * b,
-                            Div => a / b,
-                            Pow => a.pow(b.try_into()
-                                .expect("expected exponent of type u32")),
-                            Mod => a % b,
-                            _ => unreachable!()
-                        })
-                    }
-                    Equal | Inequal | Less | LessEqual | Greater | GreaterEqual => {
-                        Object::Boolean(match self.op {
-                            Equal => a == b,
-                            Inequal => a != b,
-                            Less => a < b,
-                            LessEqual => a <= b,
-                            Greater => a >= b,
-                            GreaterEqual => a >= b,
-                            _ => unreachable!()
-                        })
-                    }
-                    _ => unreachable!()
-                }
+            In => {
+                let item = self.a.eval(runtime, scope)?;
+                let collection = self.b.eval(runtime, scope)?;
+                Object::Boolean(runtime.contains(scope, collection, &item)?)
             }
 
+            // the right operand is only evaluated when it decides the result,
+            // and like in python the deciding operand is returned as it is
             And | Or => {
-                let a = expect_type!(self.a.eval(runtime, scope)?, Boolean);
-                let b = expect_type!(self.b.eval(runtime, scope)?, Boolean);
-
-                Object::Boolean(match self.op {
-                    And => a && b,
-                    Or => a || b,
-                    _ => unreachable!()
-                })
+                let a = self.a.eval(runtime, scope)?;
+                if a.truthy() == (self.op == Or) {
+                    a
+                } else {
+                    self.b.eval(runtime, scope)?
+                }
             }
 
-            RangeExcl => {
+            RangeExcl | RangeIncl => {
                 let a = expect_type!(self.a.eval(runtime, scope)?, Integer);
                 let b = expect_type!(self.b.eval(runtime, scope)?, Integer);
-                Object::List((a..b).map(|i| Object::Integer(i)).collect())
+                Object::Range(Range::new(a, b, self.op == RangeIncl))
             }
 
-            SetValue => {
-                let name = expect_type!(self.a.eval(runtime, scope)?, String);
+            SetValue | AddAssign | SubAssign | MulAssign | DivAssign | ModAssign => {
                 let value = self.b.eval(runtime, scope)?;
-                scope.update(runtime, &name, value)?;
+                assign(&self.a, self.op.compound(), value, runtime, scope)?;
                 Object::Null
             }
         })
     }
 }
+
+// assigns a value to an identifier, index, attribute or several of them,
+// for compound assignments the operator is applied to the current value first
+fn assign(target: &Node, op: Option<Operator>, value: Object, runtime: &mut Runtime, scope: &mut Scope) -> Result<()> {
+    // the values are evaluated before any of them is assigned,
+    // so that `a, b = b, a` swaps them
+    if let Node::Tuple(targets) = target {
+        let values = value.items().ok_or(ExpectedType(Type::Tuple))?;
+        if op.is_some() || values.len() != targets.len() {
+            return Err(NoMatch(value.repr()).into());
+        }
+        for (target, value) in targets.iter().zip(values) {
+            assign(target, None, value, runtime, scope)?;
+        }
+        return Ok(());
+    }
+
+    let combine = |current: &dyn Fn() -> Result<Object>| match op {
+        Some(op) => apply_operator(op, current()?, value),
+        None => Ok(value),
+    };
+
+    match target {
+        Node::Identifier(ident) => {
+            let value = combine(&|| scope.get(runtime, ident))?;
+            scope.update(ident, value)
+        }
+        Node::Index(target, index) => {
+            let target = target.eval(runtime, scope)?;
+            let index = index.eval(runtime, scope)?;
+            let value = combine(&|| target.index(index.clone()))?;
+            target.set_index(index, value)
+        }
+        Node::Attribute(target, name) => {
+            let target = target.eval(runtime, scope)?;
+            let value = combine(&|| runtime.attribute(target.clone(), name))?;
+            target.set_attribute(name, value)
+        }
+        // the parser only allows the above
+        _ => unreachable!()
+    }
+}
+
+// applies a math or comparison operator to two evaluated operands,
+// `==` works on all values, while ordering is defined in compare.rs
+fn apply_operator(op: Operator, a: Object, b: Object) -> Result<Object> {
+    // string concatenation
+    if op == Operator::Add && let Object::String(a) = a {
+        let b = expect_type!(b, String);
+        return Ok(Object::String(a + &b))
+    }
+
+    match op {
+        Operator::Equal => return Ok(Object::Boolean(a == b)),
+        Operator::Inequal => return Ok(Object::Boolean(a != b)),
+        Operator::Less | Operator::LessEqual | Operator::Greater | Operator::GreaterEqual => {
+            return Ok(comparison(op, a.compare(&b)?))
+        }
+        _ => {}
+    }
+
+    // integers are promoted to floats when mixed with floats
+    match (a, b) {
+        (Object::Integer(a), Object::Integer(b)) => integer_operator(op, a, b),
+        (a, b) => float_operator(op, as_float(a)?, as_float(b)?),
+    }
+}
+
+fn integer_operator(op: Operator, a: IntegerType, b: IntegerType) -> Result<Object> {
+    use crate::parser::tokens::Operator::*;
+
+    let result = match op {
+        Add => a.checked_add(b),
+        Sub => a.checked_sub(b),
+        Mul => a.checked_mul(b),
+        Div | Mod if b == 0 => return Err(DivisionByZero.into()),
+        Div => a.checked_div(b),
+        Mod => a.checked_rem(b),
+        Pow => match u32::try_from(b) {
+            Ok(b) => a.checked_pow(b),
+            // negative exponents give fractional results
+            Err(_) => return Ok(Object::Float((a as FloatType).powi(b))),
+        }
+        _ => unreachable!()
+    };
+
+    Ok(Object::Integer(result.ok_or(IntegerOverflow)?))
+}
+
+fn float_operator(op: Operator, a: FloatType, b: FloatType) -> Result<Object> {
+    use crate::parser::tokens::Operator::*;
+
+    Ok(Object::Float(match op {
+        Add => a + b,
+        Sub => a - b,
+        Mul => a * b,
+        Div | Mod if b == 0.0 => return Err(DivisionByZero.into()),
+        Div => a / b,
+        Mod => a % b,
+        Pow => a.powf(b),
+        _ => unreachable!()
+    }))
+}
+
+fn as_float(object: Object) -> Result<FloatType> {
+    match object {
+        Object::Integer(integer) => Ok(integer as FloatType),
+        Object::Float(float) => Ok(float),
+        _ => Err(ExpectedType(Type::Float).into())
+    }
+}
+
+// the ordering is None for values that can't be compared, like NaN
+fn comparison(op: Operator, ordering: Option<Ordering>) -> Object {
+    use crate::parser::tokens::Operator::*;
+
+    Object::Boolean(match op {
+        Less => ordering.is_some_and(Ordering::is_lt),
+        LessEqual => ordering.is_some_and(Ordering::is_le),
+        Greater => ordering.is_some_and(Ordering::is_gt),
+        GreaterEqual => ordering.is_some_and(Ordering::is_ge),
+        _ => unreachable!()
+    })
+}
@@ -0,0 +1,59 @@
+use super::*;
+
+// a class declared with `class Name(Parent) { methods }`, which is called
+// like a function to create an instance, running its `init` method if any
+#[derive(Debug)]
+pub struct Class {
+    pub name: Box<str>,
+    pub parent: Option<Rc<Class>>,
+    pub methods: Scope,
+}
+
+pub const INIT_METHOD: &str = "init";
+
+impl Class {
+    // looks up a method in the class, then in its parents
+    pub fn method(&self, name: &str) -> Option<Object> {
+        self.methods.get_local(name)
+            .or_else(|| self.parent.as_ref().and_then(|parent| parent.method(name)))
+    }
+}
+
+impl Runtime {
+    pub fn instantiate(
+        &mut self,
+        scope: &Scope,
+        class: &Rc<Class>,
+        args: Vec<Object>,
+        kwargs: Kwargs,
+    ) -> Result<Object> {
+        let instance = Object::Instance(class.clone(), Rc::new(RefCell::new(Map::new())));
+
+        match class.method(INIT_METHOD) {
+            Some(init) => {
+                let mut args = args;
+                args.insert(0, instance.clone());
+                self.call_with(scope, &init, args, kwargs)?;
+            }
+            None if !args.is_empty() => return Err(ExpectedArgs(0).into()),
+            None => if let Some((name, _)) = kwargs.first() {
+                return Err(UnknownArgument(name.clone()).into());
+            }
+        }
+
+        Ok(instance)
+    }
+}
+
+// binds a method to the object it was accessed on,
+// which leaves the remaining arguments to the caller
+pub fn bind(object: Object, method: Object) -> Object {
+    let args = match &method {
+        Object::Function { args, .. } => args.without_first(),
+        _ => Params::default(),
+    };
+    Object::Function {
+        func: Box::new(Function::Bound(object, method)),
+        args,
+    }
+}
@@ -0,0 +1,153 @@
+use super::*;
+
+// values of different types are never equal, except for integers and floats,
+// collections are compared by their contents and other objects by identity
+impl PartialEq for Object {
+    fn eq(&self, other: &Self) -> bool {
+        self.equals(other, &mut Vec::new())
+    }
+}
+
+// the addresses of the collections being compared further up
+type Comparing = Vec<(usize, usize)>;
+
+impl Object {
+    fn equals(&self, other: &Self, comparing: &mut Comparing) -> bool {
+        match (self, other) {
+            (Self::Null, Self::Null) => true,
+            (Self::String(a), Self::String(b)) => a == b,
+            (Self::Integer(a), Self::Integer(b)) => a == b,
+            (Self::Float(a), Self::Float(b)) => a == b,
+            (Self::Integer(a), Self::Float(b)) | (Self::Float(b), Self::Integer(a)) => {
+                *a as FloatType == *b
+            }
+            (Self::Boolean(a), Self::Boolean(b)) => a == b,
+            (Self::List(a), Self::List(b)) => Rc::ptr_eq(a, b) || nested_eq(a, b, comparing, |comparing| {
+                items_eq(&a.borrow(), &b.borrow(), comparing)
+            }),
+            (Self::Map(a), Self::Map(b)) => Rc::ptr_eq(a, b) || nested_eq(a, b, comparing, |comparing| {
+                map_eq(&a.borrow(), &b.borrow(), comparing)
+            }),
+            (Self::Tuple(a), Self::Tuple(b)) => items_eq(a, b, comparing),
+            (Self::Range(a), Self::Range(b)) => range_eq(a, b),
+            (Self::StructType(a), Self::StructType(b)) => Rc::ptr_eq(a, b),
+            (Self::Struct(a, a_fields), Self::Struct(b, b_fields)) => {
+                Rc::ptr_eq(a, b) && (Rc::ptr_eq(a_fields, b_fields)
+                    || nested_eq(a_fields, b_fields, comparing, |comparing| {
+                        items_eq(&a_fields.borrow(), &b_fields.borrow(), comparing)
+                    }))
+            }
+            (Self::Class(a), Self::Class(b)) => Rc::ptr_eq(a, b),
+            (Self::Instance(_, a), Self::Instance(_, b)) => Rc::ptr_eq(a, b),
+            (Self::Module(a), Self::Module(b)) => Rc::ptr_eq(a, b),
+            (Self::Iterator(a), Self::Iterator(b)) => a == b,
+            (Self::Function { func: a, .. }, Self::Function { func: b, .. }) => {
+                function_eq(a, b, comparing)
+            }
+            _ => false,
+        }
+    }
+}
+
+// collections already being compared are taken to be equal,
+// so that lists containing themselves, like `l.push(l)`, can be compared
+fn nested_eq<T>(a: &Rc<T>, b: &Rc<T>, comparing: &mut Comparing, eq: impl FnOnce(&mut Comparing) -> bool) -> bool {
+    let pair = (Rc::as_ptr(a) as usize, Rc::as_ptr(b) as usize);
+    if comparing.contains(&pair) {
+        return true
+    }
+    comparing.push(pair);
+    let equal = eq(comparing);
+    comparing.pop();
+    equal
+}
+
+fn items_eq(a: &[Object], b: &[Object], comparing: &mut Comparing) -> bool {
+    a.len() == b.len() && a.iter().zip(b).all(|(a, b)| a.equals(b, comparing))
+}
+
+// like dictionaries in python, the order of the entries doesn't matter
+fn map_eq(a: &Map, b: &Map, comparing: &mut Comparing) -> bool {
+    a.len() == b.len() && a.entries.iter()
+        .all(|(key, value)| b.get(key).is_some_and(|other| value.equals(other, comparing)))
+}
+
+// ranges are equal if they contain the same numbers, like `0..3` and `0..=2`
+fn range_eq(a: &Range, b: &Range) -> bool {
+    let len = a.len();
+    len == b.len()
+        && (len == 0 || a.start == b.start)
+        && (len <= 1 || a.step == b.step)
+}
+
+// functions are equal to the copies made of them, but not to
+// other functions made from the same definition, like closures in a loop
+fn function_eq(a: &Function, b: &Function, comparing: &mut Comparing) -> bool {
+    match (a, b) {
+        (Function::Pointer(a), Function::Pointer(b)) => std::ptr::fn_addr_eq(*a, *b),
+        (Function::Block(a, _), Function::Block(b, _))
+            | (Function::Generator(a, _), Function::Generator(b, _)) => Rc::ptr_eq(a, b),
+        (Function::Bound(a, a_method), Function::Bound(b, b_method)) => {
+            a.equals(b, comparing) && a_method.equals(b_method, comparing)
+        }
+        _ => false,
+    }
+}
+
+impl Object {
+    // orders numbers, strings and booleans, and lists and tuples lexicographically,
+    // None means the values can't be ordered, which is also the case for NaN
+    pub fn compare(&self, other: &Self) -> Result<Option<Ordering>> {
+        Ok(match (self, other) {
+            (Self::Integer(a), Self::Integer(b)) => a.partial_cmp(b),
+            (Self::Integer(_) | Self::Float(_), Self::Integer(_) | Self::Float(_)) => {
+                as_float(self.clone())?.partial_cmp(&as_float(other.clone())?)
+            }
+            (Self::String(a), Self::String(b)) => a.partial_cmp(b),
+            (Self::Boolean(a), Self::Boolean(b)) => a.partial_cmp(b),
+            (Self::List(_), Self::List(_)) | (Self::Tuple(_), Self::Tuple(_)) => {
+                let (a, b) = (self.items().unwrap(), other.items().unwrap());
+                // the first differing items decide, otherwise the shorter one comes first
+                match a.iter().zip(b.iter()).find(|(a, b)| a != b) {
+                    Some((a, b)) => a.compare(b)?,
+                    None => a.len().partial_cmp(&b.len()),
+                }
+            }
+            _ => return Err(Unordered(self.typ(), other.typ()).into())
+        })
+    }
+}
+
+impl Runtime {
+    // the `in` operator, which checks for items in lists and tuples, keys in maps,
+    // substrings in strings, and otherwise goes through the collection's iterator
+    pub fn contains(&mut self, scope: &Scope, collection: Object, item: &Object) -> Result<bool> {
+        Ok(match collection {
+            Object::List(_) | Object::Tuple(_) => collection.items().unwrap().contains(item),
+            Object::Map(map) => match item {
+                // floats are looked up like the integers they equal, as with `==`
+                Object::Float(float) => float.fract() == 0.0
+                    && IntegerType::try_from(*float as i64)
+                        .is_ok_and(|int| map.borrow().get(&Key::Integer(int)).is_some()),
+                item => map.borrow().get(&Key::try_from(item.clone())?).is_some(),
+            }
+            Object::String(string) => match item {
+                Object::String(substring) => string.contains(substring.as_str()),
+                _ => return Err(ExpectedType(Type::String).into()),
+            }
+            Object::Range(range) => match item {
+                Object::Integer(int) => range.contains(*int),
+                _ => false,
+            }
+            collection => {
+                let iter = self.iter(scope, collection)?;
+                while let Some(value) = iter.next(self)? {
+                    if value == *item {
+                        return Ok(true)
+                    }
+                }
+                false
+            }
+        })
+    }
+}
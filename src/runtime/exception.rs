@@ -0,0 +1,89 @@
+use super::*;
+
+// the kind of an error thrown with a message rather than an error value
+pub const DEFAULT_ERROR_KIND: &str = "Error";
+
+impl RuntimeError {
+    // the name of the error, as seen by scripts catching it
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ExpectedType(..) => "ExpectedType",
+            ExpectedArgs(..) => "ExpectedArgs",
+            NameError(..) => "NameError",
+            DivisionByZero => "DivisionByZero",
+            IntegerOverflow => "IntegerOverflow",
+            InvalidKey(..) => "InvalidKey",
+            KeyError(..) => "KeyError",
+            IndexError(..) => "IndexError",
+            ImmutableType(..) => "ImmutableType",
+            AttributeError(..) => "AttributeError",
+            MissingArgument(..) => "MissingArgument",
+            UnknownArgument(..) => "UnknownArgument",
+            DuplicateArgument(..) => "DuplicateArgument",
+            InvalidStep(..) => "InvalidStep",
+            Unordered(..) => "Unordered",
+            NoMatch(..) => "NoMatch",
+            InvalidControlFlow(..) => "InvalidControlFlow",
+            ImportError(..) => "ImportError",
+            CyclicImport(..) => "CyclicImport",
+            CommandError(..) => "CommandError",
+            InvalidYield => "InvalidYield",
+            IteratorRunning => "IteratorRunning",
+            StopIteration => "StopIteration",
+            Exception(..) => "Exception",
+        }
+    }
+}
+
+impl Object {
+    // creates the value of an error, which is a map with a kind and a message
+    pub fn error(kind: &str, message: &str) -> Self {
+        let mut map = Map::new();
+        map.insert(Key::String("kind".to_string()), Object::String(kind.to_string()));
+        map.insert(Key::String("message".to_string()), Object::String(message.to_string()));
+        Object::map(map)
+    }
+
+    // gets a string from a map, which is how errors store their kind and message
+    fn field_str(&self, name: &str) -> Option<String> {
+        match self {
+            Object::Map(map) => match map.borrow().get(&Key::String(name.to_string())) {
+                Some(Object::String(string)) => Some(string.clone()),
+                _ => None,
+            }
+            _ => None,
+        }
+    }
+}
+
+// turns a value given to `throw` into an error,
+// where strings become the message of a plain error
+pub fn exception(value: Object) -> RuntimeError {
+    match value {
+        Object::String(message) => Exception(Object::error(DEFAULT_ERROR_KIND, &message)),
+        value => Exception(value),
+    }
+}
+
+// the message shown for an uncaught exception
+pub fn exception_message(value: &Object) -> String {
+    match (value.field_str("kind"), value.field_str("message")) {
+        (Some(kind), Some(message)) => format!("{kind}: {message}"),
+        _ => value.repr(),
+    }
+}
+
+// turns any error into the value a `catch` block receives,
+// errors from outside the runtime (e.g. io errors) get the default kind
+pub fn caught_error(err: Box<dyn std::error::Error>) -> Object {
+    match err.downcast::<RuntimeError>() {
+        Ok(err) => match *err {
+            Exception(value) => value,
+            err => Object::error(err.kind(), &err.to_string()),
+        }
+        Err(err) if err.is::<crate::parser::SyntaxError>() => {
+            Object::error("SyntaxError", &err.to_string())
+        }
+        Err(err) => Object::error(DEFAULT_ERROR_KIND, &err.to_string()),
+    }
+}
@@ -0,0 +1,206 @@
+use super::*;
+use std::rc::Weak;
+
+// functions keep the scope they were defined in alive, and are usually stored
+// in that same scope, so reference counting alone never frees them. the scopes
+// captured by functions and classes are tracked, and from time to time the ones
+// only kept alive by such cycles are cleared, like the cycle collector of python
+pub struct Collector {
+    scopes: Vec<Weak<RefCell<ScopeData>>>,
+    // the number of tracked scopes at which the next collection happens
+    threshold: usize,
+}
+
+const MIN_THRESHOLD: usize = 1000;
+
+impl Default for Collector {
+    fn default() -> Self {
+        Self { scopes: Vec::new(), threshold: MIN_THRESHOLD }
+    }
+}
+
+// the shared values that can be part of a cycle
+enum Shared {
+    Scope(Scope),
+    List(Rc<RefCell<Vec<Object>>>),
+    Map(Rc<RefCell<Map>>),
+    Tuple(Rc<[Object]>),
+    Class(Rc<Class>),
+    Module(Rc<Module>),
+}
+
+impl Shared {
+    fn address(&self) -> usize {
+        match self {
+            Self::Scope(scope) => Rc::as_ptr(&scope.0) as *const u8 as usize,
+            Self::List(list) => Rc::as_ptr(list) as *const u8 as usize,
+            Self::Map(map) => Rc::as_ptr(map) as *const u8 as usize,
+            Self::Tuple(tuple) => Rc::as_ptr(tuple) as *const u8 as usize,
+            Self::Class(class) => Rc::as_ptr(class) as *const u8 as usize,
+            Self::Module(module) => Rc::as_ptr(module) as *const u8 as usize,
+        }
+    }
+
+    fn strong_count(&self) -> usize {
+        match self {
+            Self::Scope(scope) => Rc::strong_count(&scope.0),
+            Self::List(list) => Rc::strong_count(list),
+            Self::Map(map) => Rc::strong_count(map),
+            Self::Tuple(tuple) => Rc::strong_count(tuple),
+            Self::Class(class) => Rc::strong_count(class),
+            Self::Module(module) => Rc::strong_count(module),
+        }
+    }
+
+    // the shared values this one refers to, or None if it is in use right now
+    fn children(&self) -> Option<Vec<Shared>> {
+        let mut children = Vec::new();
+        match self {
+            Self::Scope(scope) => {
+                let data = scope.0.try_borrow().ok()?;
+                data.objects.iter().for_each(|object| object.shared(&mut children));
+                children.extend(data.parent.clone().map(Self::Scope));
+            }
+            Self::List(list) => {
+                list.try_borrow().ok()?.iter().for_each(|object| object.shared(&mut children));
+            }
+            Self::Map(map) => {
+                map.try_borrow().ok()?.entries.iter()
+                    .for_each(|(_, object)| object.shared(&mut children));
+            }
+            Self::Tuple(tuple) => tuple.iter().for_each(|object| object.shared(&mut children)),
+            Self::Class(class) => {
+                children.extend(class.parent.clone().map(Self::Class));
+                children.push(Self::Scope(class.methods.clone()));
+            }
+            Self::Module(module) => children.push(Self::Scope(module.scope.clone())),
+        }
+        Some(children)
+    }
+
+    // empties the value, returning what it held so that it's dropped later
+    fn clear(&self, garbage: &mut Vec<Object>, scopes: &mut Vec<Scope>) {
+        match self {
+            Self::Scope(scope) => {
+                let mut data = scope.0.borrow_mut();
+                garbage.append(&mut data.objects);
+                data.names.clear();
+                scopes.extend(data.parent.take());
+            }
+            Self::List(list) => garbage.append(&mut list.borrow_mut()),
+            Self::Map(map) => {
+                let map = std::mem::take(&mut *map.borrow_mut());
+                garbage.extend(map.entries.into_iter().map(|(_, object)| object));
+            }
+            // these can't be changed, but clearing the others breaks their cycles
+            Self::Tuple(_) | Self::Class(_) | Self::Module(_) => {}
+        }
+    }
+}
+
+impl Object {
+    // the shared values directly held by this object, iterators are left out,
+    // which keeps everything they refer to alive
+    fn shared(&self, shared: &mut Vec<Shared>) {
+        match self {
+            Self::List(list) | Self::Struct(_, list) => shared.push(Shared::List(list.clone())),
+            Self::Map(map) => shared.push(Shared::Map(map.clone())),
+            Self::Instance(class, map) => {
+                shared.push(Shared::Class(class.clone()));
+                shared.push(Shared::Map(map.clone()));
+            }
+            Self::Tuple(tuple) => shared.push(Shared::Tuple(tuple.clone())),
+            Self::Class(class) => shared.push(Shared::Class(class.clone())),
+            Self::Module(module) => shared.push(Shared::Module(module.clone())),
+            Self::Function { func, .. } => match func.as_ref() {
+                Function::Block(_, scope) | Function::Generator(_, scope) => {
+                    shared.push(Shared::Scope(scope.clone()))
+                }
+                Function::Bound(object, method) => {
+                    object.shared(shared);
+                    method.shared(shared);
+                }
+                Function::Pointer(_) => {}
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Runtime {
+    // tracks a scope captured by a function or class, collecting cycles now and then
+    pub fn track(&mut self, scope: &Scope) {
+        self.collector.scopes.push(Rc::downgrade(&scope.0));
+        if self.collector.scopes.len() >= self.collector.threshold {
+            self.collect();
+        }
+    }
+
+    // clears the tracked scopes, and everything reachable from them, which are
+    // only referred to by each other. a value is kept if it has more references
+    // than the values found here account for, or if something reachable keeps it
+    pub fn collect(&mut self) {
+        let collector = &mut self.collector;
+        collector.scopes.retain(|scope| scope.strong_count() > 0);
+        collector.scopes.sort_by_key(|scope| scope.as_ptr() as usize);
+        collector.scopes.dedup_by(|a, b| a.ptr_eq(b));
+
+        let mut values: Vec<Shared> = collector.scopes.iter()
+            .filter_map(|scope| scope.upgrade().map(|scope| Shared::Scope(Scope(scope))))
+            .collect();
+        let mut ids: HashMap<usize, usize> = values.iter().enumerate()
+            .map(|(id, value)| (value.address(), id))
+            .collect();
+
+        // finds everything reachable, with the references between the values
+        let mut edges: Vec<Option<Vec<usize>>> = Vec::new();
+        let mut i = 0;
+        while i < values.len() {
+            let children = values[i].children().map(|children| {
+                children.into_iter().map(|child| {
+                    *ids.entry(child.address()).or_insert_with(|| {
+                        values.push(child);
+                        values.len() - 1
+                    })
+                }).collect()
+            });
+            edges.push(children);
+            i += 1;
+        }
+
+        // the references from outside, the copies held in `values` aside
+        let mut refs: Vec<usize> = values.iter().map(|value| value.strong_count() - 1).collect();
+        for children in edges.iter().flatten() {
+            for child in children {
+                refs[*child] -= 1;
+            }
+        }
+
+        let mut alive = vec![false; values.len()];
+        let mut stack: Vec<usize> = (0..values.len())
+            .filter(|id| refs[*id] > 0 || edges[*id].is_none())
+            .collect();
+        while let Some(id) = stack.pop() {
+            if alive[id] {
+                continue
+            }
+            alive[id] = true;
+            stack.extend(edges[id].iter().flatten().filter(|child| !alive[**child]));
+        }
+
+        // the cleared values are dropped after none of them is borrowed anymore
+        let (mut garbage, mut scopes) = (Vec::new(), Vec::new());
+        for (value, alive) in values.iter().zip(&alive) {
+            if !alive {
+                value.clear(&mut garbage, &mut scopes);
+            }
+        }
+        drop(values);
+        drop(garbage);
+        drop(scopes);
+
+        let collector = &mut self.collector;
+        collector.scopes.retain(|scope| scope.strong_count() > 0);
+        collector.threshold = MIN_THRESHOLD.max(2 * collector.scopes.len());
+    }
+}
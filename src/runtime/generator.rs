@@ -0,0 +1,327 @@
+use super::*;
+use std::rc::Weak;
+
+// generators are functions containing `yield`, which are run a step at a time,
+// so their statements are kept on a stack of frames instead of the rust stack
+pub struct Generator {
+    stack: Vec<Frame>,
+    // where the generator goes when it's dropped with finally blocks left to run
+    closing: Weak<RefCell<Vec<Generator>>>,
+}
+
+// loops and try statements are always statements of a block, so their frames
+// keep the block and the index of the statement instead of copying it
+enum Frame {
+    Block {
+        block: Block,
+        index: usize,
+        scope: Scope,
+    },
+    While {
+        block: Block,
+        index: usize,
+        scope: Scope,
+    },
+    For {
+        block: Block,
+        index: usize,
+        iter: Iter,
+        scope: Scope,
+    },
+    Try {
+        block: Block,
+        index: usize,
+        scope: Scope,
+        state: TryState,
+    },
+}
+
+enum TryState {
+    Body,
+    Catch,
+    // the error is raised again after the finally block
+    Finally(Option<Box<dyn std::error::Error>>),
+}
+
+// the blocks of try statements are always blocks
+fn block_frame(node: &Node, scope: Scope) -> Frame {
+    match node {
+        Node::Block(block) => Frame::Block {
+            block: block.clone(),
+            index: 0,
+            scope: Scope::new(Some(scope)),
+        },
+        _ => unreachable!()
+    }
+}
+
+impl Generator {
+    pub fn new(runtime: &Runtime, block: Block, scope: Scope) -> Self {
+        Self {
+            stack: vec![Frame::Block { block, index: 0, scope }],
+            closing: Rc::downgrade(&runtime.closing),
+        }
+    }
+
+    // runs the frame on top of the stack, returning the yielded value if any
+    fn step(&mut self, runtime: &mut Runtime) -> Result<Option<Object>> {
+        let frame = self.stack.last_mut().unwrap();
+
+        match frame {
+            Frame::Block { block, index, scope } => {
+                if *index < block.statements.len() {
+                    let (block, current, scope) = (block.clone(), *index, scope.clone());
+                    *index += 1;
+                    self.exec(&block, current, scope, runtime)
+                } else {
+                    self.stack.pop();
+                    Ok(None)
+                }
+            }
+            Frame::While { block, index, scope } => {
+                let (block, index, scope) = (block.clone(), *index, scope.clone());
+                let Node::Statement(Statement::While(condition, body)) = &block.statements[index]
+                else { unreachable!() };
+                if condition.eval(runtime, &mut scope.clone())?.truthy() {
+                    self.exec_node(body, scope, runtime)
+                } else {
+                    self.stack.pop();
+                    Ok(None)
+                }
+            }
+            Frame::For { block, index, iter, scope } => {
+                let (block, index, iter) = (block.clone(), *index, iter.clone());
+                let scope = Scope::new(Some(scope.clone()));
+                let Node::Statement(Statement::For(pattern, _, body)) = &block.statements[index]
+                else { unreachable!() };
+                match iter.next(runtime)? {
+                    Some(item) => {
+                        if !pattern.matches(&item, &scope) {
+                            return Err(NoMatch(item.repr()).into());
+                        }
+                        self.exec_node(body, scope, runtime)
+                    }
+                    None => {
+                        self.stack.pop();
+                        Ok(None)
+                    }
+                }
+            }
+            // the block or the catch block has finished
+            Frame::Try { block, index, scope, state } => {
+                let block = block.clone();
+                let Node::Statement(Statement::Try(_, _, finally)) = &block.statements[*index]
+                else { unreachable!() };
+                match state {
+                    TryState::Body | TryState::Catch if finally.is_some() => {
+                        let finally = block_frame(finally.as_ref().unwrap(), scope.clone());
+                        *state = TryState::Finally(None);
+                        self.stack.push(finally);
+                        Ok(None)
+                    }
+                    TryState::Body | TryState::Catch => {
+                        self.stack.pop();
+                        Ok(None)
+                    }
+                    TryState::Finally(err) => {
+                        let err = err.take();
+                        self.stack.pop();
+                        match err {
+                            Some(err) => Err(err),
+                            None => Ok(None),
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    // runs a statement of a block, where loops and try statements containing `yield`
+    // are pushed as frames, and the other statements are run by exec_node
+    fn exec(&mut self, block: &Block, index: usize, scope: Scope, runtime: &mut Runtime) -> Result<Option<Object>> {
+        let node = &block.statements[index];
+        if !block.yields()[index] {
+            node.eval(runtime, &mut scope.clone())?;
+            return Ok(None);
+        }
+
+        match node {
+            Node::Statement(Statement::While(..)) => {
+                self.stack.push(Frame::While { block: block.clone(), index, scope });
+                Ok(None)
+            }
+            Node::Statement(Statement::For(_, sequence, _)) => {
+                let sequence = sequence.eval(runtime, &mut scope.clone())?;
+                self.stack.push(Frame::For {
+                    block: block.clone(),
+                    index,
+                    iter: runtime.iter(&scope, sequence)?,
+                    scope,
+                });
+                Ok(None)
+            }
+            Node::Statement(Statement::Try(body, ..)) => {
+                self.stack.push(Frame::Try {
+                    block: block.clone(),
+                    index,
+                    scope: scope.clone(),
+                    state: TryState::Body,
+                });
+                self.stack.push(block_frame(body, scope));
+                Ok(None)
+            }
+            node => self.exec_node(node, scope, runtime),
+        }
+    }
+
+    // runs the body of an if statement, a loop or a match arm
+    fn exec_node(&mut self, node: &Node, scope: Scope, runtime: &mut Runtime) -> Result<Option<Object>> {
+        if !node.contains_yield() {
+            node.eval(runtime, &mut scope.clone())?;
+            return Ok(None);
+        }
+
+        match node {
+            Node::Statement(Statement::Yield(value)) => Ok(Some(match value {
+                Some(value) => value.eval(runtime, &mut scope.clone())?,
+                None => Object::Null,
+            })),
+            Node::Block(block) => {
+                self.stack.push(Frame::Block {
+                    block: block.clone(),
+                    index: 0,
+                    scope: Scope::new(Some(scope)),
+                });
+                Ok(None)
+            }
+            Node::Statement(Statement::If(condition, block, ext)) => {
+                if condition.eval(runtime, &mut scope.clone())?.truthy() {
+                    self.exec_node(block, scope, runtime)
+                } else if let Some(ext) = ext {
+                    self.exec_node(ext, scope, runtime)
+                } else {
+                    Ok(None)
+                }
+            }
+            Node::Match(value, arms) => {
+                let value = value.eval(runtime, &mut scope.clone())?;
+                let (arm, scope) = runtime.match_arm(&scope, &value, arms)?;
+                self.exec_node(&arm.body, scope, runtime)
+            }
+            // loops and try statements only appear in blocks,
+            // and contains_yield only looks into the nodes above
+            _ => unreachable!()
+        }
+    }
+
+    // pops frames until one of them handles the error, like a loop
+    // handling break, or returns the error if none of them does
+    fn unwind(&mut self, err: Box<dyn std::error::Error>) -> Result<()> {
+        while let Some(frame) = self.stack.last_mut() {
+            match frame {
+                Frame::While { .. } | Frame::For { .. } => {
+                    match err.downcast_ref::<ControlFlow>() {
+                        Some(ControlFlow::Break) => {
+                            self.stack.pop();
+                            return Ok(());
+                        }
+                        Some(ControlFlow::Continue) => return Ok(()),
+                        _ => {}
+                    }
+                }
+                Frame::Try { block, index, scope, state } => {
+                    let block = block.clone();
+                    let Node::Statement(Statement::Try(_, catch, finally)) = &block.statements[*index]
+                    else { unreachable!() };
+                    match state {
+                        // control flow isn't an error, so it passes through
+                        TryState::Body if catch.is_some() && !err.is::<ControlFlow>() => {
+                            let (ident, handler) = catch.as_ref().unwrap();
+                            let scope = Scope::new(Some(scope.clone()));
+                            if let Some(ident) = ident {
+                                scope.define(ident, caught_error(err));
+                            }
+                            *state = TryState::Catch;
+                            self.stack.push(block_frame(handler, scope));
+                            return Ok(());
+                        }
+                        TryState::Body | TryState::Catch if finally.is_some() => {
+                            let finally = block_frame(finally.as_ref().unwrap(), scope.clone());
+                            *state = TryState::Finally(Some(err));
+                            self.stack.push(finally);
+                            return Ok(());
+                        }
+                        _ => {}
+                    }
+                }
+                Frame::Block { .. } => {}
+            }
+            self.stack.pop();
+        }
+
+        // a return ends the generator
+        match err.downcast::<ControlFlow>() {
+            Ok(flow) => match *flow {
+                ControlFlow::Return(_) => Ok(()),
+                flow => Err(InvalidControlFlow(flow).into()),
+            }
+            Err(err) => Err(err),
+        }
+    }
+}
+
+impl ObjectIterator for Generator {
+    fn next(&mut self, runtime: &mut Runtime) -> Result<Option<Object>> {
+        while !self.stack.is_empty() {
+            match self.step(runtime) {
+                Ok(Some(value)) => return Ok(Some(value)),
+                Ok(None) => {}
+                Err(err) => if let Err(err) = self.unwind(err) {
+                    // a failed generator is exhausted
+                    self.stack.clear();
+                    return Err(err);
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
+    // runs the finally blocks left, as if the generator returned where it stopped,
+    // anything it yields on the way is ignored, like in python
+    fn close(&mut self, runtime: &mut Runtime) -> Result<()> {
+        let result = match self.unwind(ControlFlow::Return(Object::Null).into()) {
+            Ok(()) => self.next(runtime).map(|_| ()),
+            Err(err) => Err(err),
+        };
+        self.stack.clear();
+        result
+    }
+}
+
+// a generator stopped inside a try statement with a finally block,
+// which needs the runtime to be closed, so it's closed after the current statement
+impl Drop for Generator {
+    fn drop(&mut self) {
+        let pending = self.stack.iter().any(|frame| matches!(frame, Frame::Try { .. }));
+        if let (true, Some(closing)) = (pending, self.closing.upgrade()) {
+            closing.borrow_mut().push(Generator {
+                stack: std::mem::take(&mut self.stack),
+                closing: Weak::new(),
+            });
+        }
+    }
+}
+
+impl Runtime {
+    // closes the generators dropped since this was last called
+    pub fn close_dropped(&mut self) -> Result<()> {
+        loop {
+            let generator = self.closing.borrow_mut().pop();
+            match generator {
+                Some(mut generator) => generator.close(self)?,
+                None => return Ok(()),
+            }
+        }
+    }
+}
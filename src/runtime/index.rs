@@ -0,0 +1,129 @@
+use super::*;
+
+// converts a possibly negative index to a position within the length,
+// which is done in i64, as ranges may be longer than the largest integer
+fn position(index: IntegerType, len: usize) -> Result<usize> {
+    let len = len as i64;
+    let position = if index < 0 {
+        len + index as i64
+    } else {
+        index as i64
+    };
+
+    if 0 <= position && position < len {
+        Ok(position as usize)
+    } else {
+        Err(IndexError(index).into())
+    }
+}
+
+// like position, but clamped to the bounds instead of failing
+fn bound(index: Option<IntegerType>, len: usize, default: usize) -> usize {
+    match index {
+        Some(index) if index < 0 => (len as i64 + index as i64).max(0) as usize,
+        Some(index) => (index as usize).min(len),
+        None => default,
+    }
+}
+
+impl Object {
+    pub fn index(&self, index: Object) -> Result<Object> {
+        if let Self::Range(range) = index {
+            return self.slice_range(range);
+        }
+
+        match self {
+            Self::List(list) => {
+                let list = list.borrow();
+                let index = expect_type!(index, Integer);
+                Ok(list[position(index, list.len())?].clone())
+            }
+            Self::Tuple(items) => {
+                let index = expect_type!(index, Integer);
+                Ok(items[position(index, items.len())?].clone())
+            }
+            Self::String(string) => {
+                let index = expect_type!(index, Integer);
+                let position = position(index, string.chars().count())?;
+                Ok(Self::String(string.chars().nth(position).unwrap().to_string()))
+            }
+            Self::Range(range) => {
+                let index = expect_type!(index, Integer);
+                Ok(Self::Integer(range.get(position(index, range.len())?)))
+            }
+            Self::Map(map) => {
+                let key = index.try_into()?;
+                match map.borrow().get(&key) {
+                    Some(value) => Ok(value.clone()),
+                    None => Err(KeyError(key).into())
+                }
+            }
+            _ => Err(ExpectedType(Type::List).into())
+        }
+    }
+
+    pub fn set_index(&self, index: Object, value: Object) -> Result<()> {
+        match self {
+            Self::List(list) => {
+                let mut list = list.borrow_mut();
+                let index = expect_type!(index, Integer);
+                let position = position(index, list.len())?;
+                list[position] = value;
+                Ok(())
+            }
+            Self::Map(map) => {
+                map.borrow_mut().insert(index.try_into()?, value);
+                Ok(())
+            }
+            Self::String(_) | Self::Range(_) | Self::Tuple(_) => Err(ImmutableType(self.typ()).into()),
+            _ => Err(ExpectedType(Type::List).into())
+        }
+    }
+
+    // a slice taking every step:th item, only counting forwards
+    fn slice_range(&self, range: Range) -> Result<Object> {
+        if range.step < 1 {
+            return Err(InvalidStep(range.step).into());
+        }
+
+        // an inclusive end of -1 means the end of the sequence
+        let end = match range.inclusive {
+            true if range.end == -1 => None,
+            true => Some(range.end + 1),
+            false => Some(range.end),
+        };
+
+        let slice = self.slice(Some(range.start), end)?;
+        if range.step == 1 {
+            return Ok(slice);
+        }
+
+        Ok(match slice {
+            Self::List(list) => Self::list(list.borrow().iter()
+                .step_by(range.step as usize).cloned().collect()),
+            Self::String(string) => Self::String(string.chars()
+                .step_by(range.step as usize).collect()),
+            _ => unreachable!()
+        })
+    }
+
+    // slices are copies, which never fail for out of range bounds
+    pub fn slice(&self, start: Option<IntegerType>, end: Option<IntegerType>) -> Result<Object> {
+        match self {
+            Self::Range(range) => range.to_list().slice(start, end),
+            Self::List(list) => {
+                let list = list.borrow();
+                let start = bound(start, list.len(), 0);
+                let end = bound(end, list.len(), list.len()).max(start);
+                Ok(Self::list(list[start..end].to_vec()))
+            }
+            Self::String(string) => {
+                let len = string.chars().count();
+                let start = bound(start, len, 0);
+                let end = bound(end, len, len).max(start);
+                Ok(Self::String(string.chars().skip(start).take(end - start).collect()))
+            }
+            _ => Err(ExpectedType(Type::List).into())
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use super::*;
+
+// the iterator protocol, which `for` loops and the `iter` and `next` builtins use,
+// host applications can implement it to make their own objects iterable
+pub trait ObjectIterator {
+    // gets the next item, or None when the iterator is exhausted
+    fn next(&mut self, runtime: &mut Runtime) -> Result<Option<Object>>;
+
+    // stops the iterator before it is exhausted, letting it clean up
+    fn close(&mut self, _runtime: &mut Runtime) -> Result<()> {
+        Ok(())
+    }
+}
+
+// a shared iterator object, which is advanced by everyone holding it
+#[derive(Clone)]
+pub struct Iter(Rc<RefCell<dyn ObjectIterator>>);
+
+// adapts a rust iterator to the protocol
+struct FromIter<I>(I);
+
+impl<I: Iterator<Item = Object>> ObjectIterator for FromIter<I> {
+    fn next(&mut self, _runtime: &mut Runtime) -> Result<Option<Object>> {
+        Ok(self.0.next())
+    }
+}
+
+impl Iter {
+    pub fn new(iter: impl ObjectIterator + 'static) -> Self {
+        Self(Rc::new(RefCell::new(iter)))
+    }
+
+    pub fn adapt(iter: impl Iterator<Item = Object> + 'static) -> Self {
+        Self::new(FromIter(iter))
+    }
+
+    pub fn next(&self, runtime: &mut Runtime) -> Result<Option<Object>> {
+        // e.g. a generator advancing itself
+        let mut iter = self.0.try_borrow_mut().map_err(|_| IteratorRunning)?;
+        iter.next(runtime)
+    }
+
+    pub fn close(&self, runtime: &mut Runtime) -> Result<()> {
+        let mut iter = self.0.try_borrow_mut().map_err(|_| IteratorRunning)?;
+        iter.close(runtime)
+    }
+}
+
+// iterators are only equal to themselves
+impl PartialEq for Iter {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl fmt::Debug for Iter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Iter")
+    }
+}
+
+// the method making instances of a class iterable, which returns an iterable
+pub const ITER_METHOD: &str = "iter";
+
+impl Object {
+    pub fn iterator(iter: impl Iterator<Item = Object> + 'static) -> Self {
+        Self::Iterator(Iter::adapt(iter))
+    }
+}
+
+impl Runtime {
+    // gets an iterator over a value, where lists and tuples are copied,
+    // as the loop body might change them
+    pub fn iter(&mut self, scope: &Scope, object: Object) -> Result<Iter> {
+        Ok(match object {
+            Object::Iterator(iter) => iter,
+            Object::List(list) => Iter::adapt(list.borrow().clone().into_iter()),
+            Object::Tuple(items) => Iter::adapt((0..items.len()).map(move |i| items[i].clone())),
+            Object::String(string) => {
+                let chars: Vec<_> = string.chars().collect();
+                Iter::adapt(chars.into_iter().map(|ch| Object::String(ch.to_string())))
+            }
+            // maps are iterated by their keys
+            Object::Map(map) => {
+                let keys: Vec<_> = map.borrow().entries.iter()
+                    .map(|(key, _)| key.clone().into())
+                    .collect();
+                Iter::adapt(keys.into_iter())
+            }
+            Object::Range(range) => Iter::adapt(range.iter().map(Object::Integer)),
+            Object::Instance(ref class, _) => match class.method(ITER_METHOD) {
+                Some(method) => {
+                    let iterable = self.call(scope, &bind(object.clone(), method), Vec::new())?;
+                    return self.iter(scope, iterable);
+                }
+                None => return Err(ExpectedType(Type::Iterator).into())
+            }
+            _ => return Err(ExpectedType(Type::Iterator).into())
+        })
+    }
+}
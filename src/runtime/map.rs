@@ -0,0 +1,87 @@
+use super::*;
+use std::fmt;
+
+// the types of objects that can be used as keys in maps
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Key {
+    String(String),
+    Integer(IntegerType),
+}
+
+// maps keep the order in which keys were inserted
+#[derive(Debug, Clone, Default)]
+pub struct Map {
+    pub keys: HashMap<Key, usize>,
+    pub entries: Vec<(Key, Object)>,
+}
+
+impl Map {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, key: &Key) -> Option<&Object> {
+        self.keys.get(key).map(|id| &self.entries[*id].1)
+    }
+
+    pub fn insert(&mut self, key: Key, value: Object) {
+        if let Some(id) = self.keys.get(&key) {
+            self.entries[*id].1 = value;
+        } else {
+            self.keys.insert(key.clone(), self.entries.len());
+            self.entries.push((key, value));
+        }
+    }
+
+    pub fn remove(&mut self, key: &Key) -> Option<Object> {
+        let removed = self.keys.remove(key)?;
+        let (_, value) = self.entries.remove(removed);
+
+        // the following entries have moved one step back
+        for id in self.keys.values_mut() {
+            if *id > removed {
+                *id -= 1;
+            }
+        }
+
+        Some(value)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl TryFrom<Object> for Key {
+    type Error = Box<dyn std::error::Error>;
+
+    fn try_from(object: Object) -> Result<Self> {
+        match object {
+            Object::String(string) => Ok(Key::String(string)),
+            Object::Integer(integer) => Ok(Key::Integer(integer)),
+            _ => Err(InvalidKey(object.typ()).into())
+        }
+    }
+}
+
+impl From<Key> for Object {
+    fn from(key: Key) -> Self {
+        match key {
+            Key::String(string) => Object::String(string),
+            Key::Integer(integer) => Object::Integer(integer),
+        }
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Key::String(string) => write!(f, "{string:?}"),
+            Key::Integer(integer) => write!(f, "{integer}"),
+        }
+    }
+}
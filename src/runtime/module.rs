@@ -0,0 +1,64 @@
+use super::*;
+use std::path::PathBuf;
+
+// a file evaluated by `import`, of which only the exported names are visible
+#[derive(Debug)]
+pub struct Module {
+    pub path: Box<str>,
+    pub scope: Scope,
+    pub exports: Vec<Box<str>>,
+}
+
+impl Module {
+    // gets an exported name, which reflects later changes made by the module
+    pub fn get(&self, name: &str) -> Option<Object> {
+        if self.exports.iter().any(|export| **export == *name) {
+            self.scope.get_local(name)
+        } else {
+            None
+        }
+    }
+}
+
+impl Runtime {
+    // evaluates a module the first time it is imported,
+    // later imports of the same file get the cached module
+    pub fn import(&mut self, path: &str) -> Result<Rc<Module>> {
+        let key = std::fs::canonicalize(path)
+            .map_err(|err| ImportError(path.into(), err.to_string()))?;
+
+        match self.modules.get(&key) {
+            Some(Some(module)) => return Ok(module.clone()),
+            // the module is imported while it is still being evaluated
+            Some(None) => return Err(CyclicImport(path.into()).into()),
+            None => {}
+        }
+
+        self.modules.insert(key.clone(), None);
+        let module = self.load_module(path, &key);
+
+        match &module {
+            Ok(module) => { self.modules.insert(key, Some(module.clone())); }
+            // a failed import may be retried
+            Err(_) => { self.modules.remove(&key); }
+        }
+
+        module
+    }
+
+    fn load_module(&mut self, path: &str, key: &PathBuf) -> Result<Rc<Module>> {
+        let buffer = std::fs::read_to_string(key)
+            .map_err(|err| ImportError(path.into(), err.to_string()))?;
+        let block = crate::parse(buffer)?;
+
+        // modules don't see the names of the importing file, only the globals
+        let mut scope = Scope::new(None);
+        block.eval(self, &mut scope)?;
+
+        Ok(Rc::new(Module {
+            path: path.into(),
+            scope,
+            exports: block.exports(),
+        }))
+    }
+}
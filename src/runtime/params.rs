@@ -0,0 +1,86 @@
+use super::*;
+
+// takes a keyword argument out of the ones given to a call
+fn take(kwargs: &mut Kwargs, name: &str) -> Option<Object> {
+    let position = kwargs.iter().position(|(key, _)| **key == *name)?;
+    Some(kwargs.remove(position).1)
+}
+
+impl Runtime {
+    // defines the arguments of a call in the scope of the function,
+    // defaults are evaluated in that scope, so they can use earlier parameters
+    pub fn bind_args(
+        &mut self,
+        params: &Params,
+        scope: &mut Scope,
+        args: Vec<Object>,
+        mut kwargs: Kwargs,
+    ) -> Result<()> {
+        // otherwise the second one would be reported as unknown
+        for (i, (key, _)) in kwargs.iter().enumerate() {
+            if kwargs[..i].iter().any(|(other, _)| other == key) {
+                return Err(DuplicateArgument(key.clone()).into());
+            }
+        }
+
+        let mut args = args.into_iter();
+
+        for param in &params.positional {
+            let value = match args.next() {
+                Some(_) if kwargs.iter().any(|(key, _)| *key == param.name) => {
+                    return Err(DuplicateArgument(param.name.clone()).into());
+                }
+                Some(value) => value,
+                None => self.missing_arg(param, scope, &mut kwargs)?,
+            };
+            scope.define(&param.name, value);
+        }
+
+        let rest: Vec<Object> = args.collect();
+        match &params.rest {
+            Some(name) => scope.define(name, Object::list(rest)),
+            None if !rest.is_empty() => {
+                return Err(ExpectedArgs(params.positional.len()).into());
+            }
+            None => {}
+        }
+
+        for param in &params.keyword {
+            let value = self.missing_arg(param, scope, &mut kwargs)?;
+            scope.define(&param.name, value);
+        }
+
+        match &params.options {
+            Some(name) => {
+                let mut options = Map::new();
+                for (key, value) in kwargs {
+                    options.insert(Key::String(key.to_string()), value);
+                }
+                scope.define(name, Object::map(options));
+            }
+            None => if let Some((key, _)) = kwargs.first() {
+                return Err(UnknownArgument(key.clone()).into());
+            }
+        }
+
+        Ok(())
+    }
+
+    // gets an argument that wasn't given by position,
+    // either from the keyword arguments or the default value
+    fn missing_arg(
+        &mut self,
+        param: &Param,
+        scope: &mut Scope,
+        kwargs: &mut Kwargs,
+    ) -> Result<Object> {
+        if let Some(value) = take(kwargs, &param.name) {
+            return Ok(value);
+        }
+
+        match &param.default {
+            Some(default) => default.eval(self, scope),
+            None => Err(MissingArgument(param.name.clone()).into()),
+        }
+    }
+}
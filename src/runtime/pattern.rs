@@ -0,0 +1,62 @@
+use super::*;
+
+impl Pattern {
+    // checks whether a value matches the pattern,
+    // defining the names it binds in the given scope
+    pub fn matches(&self, value: &Object, scope: &Scope) -> bool {
+        match self {
+            Self::Wildcard => true,
+            Self::Binding(ident) => {
+                scope.define(ident, value.clone());
+                true
+            }
+            Self::Literal(literal) => literal_value(literal) == *value,
+            Self::Range(start, end, inclusive) => match value {
+                Object::Integer(int) => Range::new(*start, *end, *inclusive).contains(*int),
+                _ => false,
+            }
+            Self::List(items) | Self::Tuple(items) => match value.items() {
+                Some(values) => {
+                    values.len() == items.len()
+                        && items.iter().zip(values.iter()).all(|(item, value)| item.matches(value, scope))
+                }
+                None => false,
+            }
+            Self::Alternatives(alternatives) => {
+                alternatives.iter().any(|alternative| alternative.matches(value, scope))
+            }
+        }
+    }
+}
+
+impl Runtime {
+    // finds the first arm whose pattern matches and whose guard holds,
+    // together with the scope of the names the pattern binds
+    pub fn match_arm<'a>(&mut self, scope: &Scope, value: &Object, arms: &'a [MatchArm]) -> Result<(&'a MatchArm, Scope)> {
+        for arm in arms {
+            let mut scope = Scope::new(Some(scope.clone()));
+            if !arm.pattern.matches(value, &scope) {
+                continue
+            }
+            if let Some(guard) = &arm.guard
+                && !guard.eval(self, &mut scope)?.truthy()
+            {
+                continue
+            }
+            return Ok((arm, scope));
+        }
+        Err(NoMatch(value.repr()).into())
+    }
+}
+
+// literal patterns compare like `==`
+fn literal_value(literal: &Node) -> Object {
+    match literal {
+        Node::String(string) => Object::String(string.to_string()),
+        Node::Integer(int) => Object::Integer(*int),
+        Node::Float(float) => Object::Float(*float),
+        Node::Boolean(boolean) => Object::Boolean(*boolean),
+        // the parser only allows the above
+        _ => unreachable!()
+    }
+}
@@ -0,0 +1,63 @@
+use super::*;
+
+// ranges are lazy, their values are only computed when needed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Range {
+    pub start: IntegerType,
+    pub end: IntegerType,
+    pub step: IntegerType,
+    pub inclusive: bool,
+}
+
+impl Range {
+    pub fn new(start: IntegerType, end: IntegerType, inclusive: bool) -> Self {
+        Self {
+            start,
+            end,
+            step: 1,
+            inclusive,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        let (start, step) = (self.start as i64, self.step as i64);
+        let mut end = self.end as i64;
+        if self.inclusive {
+            end += step.signum();
+        }
+
+        let span = end - start;
+        if span.signum() != step.signum() {
+            0
+        } else {
+            // rounded up, as the last step doesn't have to be whole
+            ((span.abs() + step.abs() - 1) / step.abs()) as usize
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // the math is done in i64, as ranges may have more items than fit in an integer,
+    // while the items themselves always lie between the start and the end
+    pub fn get(&self, position: usize) -> IntegerType {
+        let value = self.start as i64 + position as i64 * self.step as i64;
+        value as IntegerType
+    }
+
+    pub fn contains(&self, value: IntegerType) -> bool {
+        let offset = value as i64 - self.start as i64;
+        let step = self.step as i64;
+        offset % step == 0 && offset / step >= 0 && ((offset / step) as usize) < self.len()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = IntegerType> + use<> {
+        let range = *self;
+        (0..self.len()).map(move |position| range.get(position))
+    }
+
+    pub fn to_list(&self) -> Object {
+        Object::list(self.iter().map(Object::Integer).collect())
+    }
+}
@@ -0,0 +1,70 @@
+use super::*;
+
+// a user defined record type, declared with `struct Name { fields }`,
+// which is called like a function to create an instance
+#[derive(Debug)]
+pub struct StructType {
+    pub name: Box<str>,
+    pub fields: Vec<Box<str>>,
+}
+
+impl StructType {
+    // the fields are given in order or by keyword, like arguments
+    pub fn instantiate(
+        self: &Rc<Self>,
+        runtime: &mut Runtime,
+        args: Vec<Object>,
+        kwargs: Kwargs,
+    ) -> Result<Object> {
+        let params = Params {
+            positional: self.fields.iter()
+                .map(|name| Param { name: name.clone(), default: None })
+                .collect(),
+            ..Params::default()
+        };
+
+        let mut scope = Scope::new(None);
+        runtime.bind_args(&params, &mut scope, args, kwargs)?;
+        let values = self.fields.iter()
+            .map(|name| scope.get_local(name).unwrap())
+            .collect();
+
+        Ok(Object::Struct(self.clone(), Rc::new(RefCell::new(values))))
+    }
+
+    fn position(&self, field: &str) -> Option<usize> {
+        self.fields.iter().position(|name| **name == *field)
+    }
+}
+
+impl Object {
+    // gets a field of a struct or an instance of a class
+    pub fn field(&self, name: &str) -> Option<Object> {
+        match self {
+            Self::Struct(typ, values) => {
+                let position = typ.position(name)?;
+                Some(values.borrow()[position].clone())
+            }
+            Self::Instance(_, fields) => fields.borrow()
+                .get(&Key::String(name.to_string())).cloned(),
+            _ => None
+        }
+    }
+
+    // sets a field of a struct or an instance, or a value in a map
+    pub fn set_attribute(&self, name: &str, value: Object) -> Result<()> {
+        match self {
+            Self::Struct(typ, values) => {
+                let position = typ.position(name)
+                    .ok_or(AttributeError(self.typ(), name.into()))?;
+                values.borrow_mut()[position] = value;
+                Ok(())
+            }
+            Self::Map(map) | Self::Instance(_, map) => {
+                map.borrow_mut().insert(Key::String(name.to_string()), value);
+                Ok(())
+            }
+            _ => Err(ImmutableType(self.typ()).into())
+        }
+    }
+}
@@ -19,28 +19,42 @@ macro_rules! get {
 
 use crate::get;
 
+// the parameters are written like in scripts, so they may have
+// default values and collect the rest of the arguments
 macro_rules! add {
     ($scope:expr,
-        $($name:ident($($arg:ident$(,)?)*);)*) => {
+        $($name:ident($($params:tt)*);)*) => {
 
         $(
             $scope.define(stringify!($name),
                 Object::Function {
                     func: Box::new(Function::Pointer($name)),
-                    args: vec![$( stringify!($arg).into(), )*],
+                    args: $crate::parser::parse_params(stringify!($($params)*))
+                        .expect(concat!("invalid params: ", stringify!($name))),
                 }
             );
         )*
     }
 }
 
-pub fn init(scope: &mut Scope) {
+// declared after the macros, so that it can use them
+mod methods;
+
+pub fn init(runtime: &mut Runtime) {
+    let scope = &mut runtime.globals;
+
     // this is such a sexy macro
     add!(scope,
-        println(text);
-        print(text);
+        println(*values, sep = " ");
+        print(*values, sep = " ");
         call(cmd);
         source(path);
         tostring(value);
+        typename(value);
+        iter(value);
+        next(iterator);
+        close(iterator);
     );
+
+    methods::init(runtime);
 }
@@ -1,34 +1,61 @@
 use super::*;
-use crate::execute;
+use crate::run;
 use std::process::Command;
 
+// the values to print, separated by `sep`
+fn joined(runtime: &mut Runtime, scope: &mut Scope) -> Result<String> {
+    let values = get!(runtime, scope, values, List);
+    let sep = get!(runtime, scope, sep, String);
+    let values: Vec<_> = values.borrow().iter().map(Object::to_string).collect();
+    Ok(values.join(&sep))
+}
+
 pub fn println(runtime: &mut Runtime, scope: &mut Scope) -> Result<Object> {
-    println!("{}", get!(runtime, scope, text, String));
+    println!("{}", joined(runtime, scope)?);
     Ok(Object::Null)
 }
 
 pub fn print(runtime: &mut Runtime, scope: &mut Scope) -> Result<Object> {
-    print!("{}", get!(runtime, scope, text, String));
+    print!("{}", joined(runtime, scope)?);
     Ok(Object::Null)
 }
 
+// runs a file in the calling scope, unlike `import`
 pub fn source(runtime: &mut Runtime, scope: &mut Scope) -> Result<Object> {
     let path = get!(runtime, scope, path, String);
-    let scope: &mut Scope = unsafe { &mut *scope.parent.unwrap() };
+    let mut scope = scope.parent().unwrap();
     let buffer = std::fs::read_to_string(&path)
-        .unwrap_or_else(|e| panic!("Could not read file {path}: {e}"));
-    execute(runtime, scope, buffer);
+        .map_err(|err| RuntimeError::ImportError(path.into(), err.to_string()))?;
+    run(runtime, &mut scope, buffer)?;
     Ok(Object::Null)
 }
 
 pub fn tostring(runtime: &mut Runtime, scope: &mut Scope) -> Result<Object> {
-    // TODO use the same formatting as parser::node::Node
     let obj = scope.get(runtime, "value")?;
-    Ok(Object::String(match obj {
-        Object::String(string) => string,
-        Object::Integer(integer) => integer.to_string(),
-        _ => format!("{obj:?}")
-    }))
+    Ok(Object::String(obj.to_string()))
+}
+
+pub fn typename(runtime: &mut Runtime, scope: &mut Scope) -> Result<Object> {
+    let obj = scope.get(runtime, "value")?;
+    Ok(Object::String(obj.type_name()))
+}
+
+pub fn iter(runtime: &mut Runtime, scope: &mut Scope) -> Result<Object> {
+    let value = scope.get(runtime, "value")?;
+    Ok(Object::Iterator(runtime.iter(scope, value)?))
+}
+
+// gets the next item of an iterator, failing when it is exhausted
+pub fn next(runtime: &mut Runtime, scope: &mut Scope) -> Result<Object> {
+    let iter = get!(runtime, scope, iterator, Iterator);
+    iter.next(runtime)?.ok_or(RuntimeError::StopIteration.into())
+}
+
+// stops an iterator early, which runs the finally blocks left in a generator
+pub fn close(runtime: &mut Runtime, scope: &mut Scope) -> Result<Object> {
+    let iter = get!(runtime, scope, iterator, Iterator);
+    iter.close(runtime)?;
+    Ok(Object::Null)
 }
 
 pub fn call(runtime: &mut Runtime, scope: &mut Scope) -> Result<Object> {
@@ -38,19 +65,21 @@ pub fn call(runtime: &mut Runtime, scope: &mut Scope) -> Result<Object> {
             ("sh", "-c")
         };
 
-    let stdout = Command::new(shell).arg(flag)
-        .arg(get!(runtime, scope, cmd, String))
+    let cmd = get!(runtime, scope, cmd, String);
+    let output = Command::new(shell).arg(flag)
+        .arg(&cmd)
         .output()
-        .expect("command failed")
-        .stdout; // TODO fix
+        .map_err(|err| RuntimeError::CommandError(cmd.clone(), err.to_string()))?;
+
+    if !output.status.success() {
+        return Err(RuntimeError::CommandError(cmd, output.status.to_string()).into());
+    }
 
-    let mut stdout: String = stdout.iter().map(|b| *b as char).collect();
+    let mut stdout: String = output.stdout.iter().map(|b| *b as char).collect();
     
     // remove trailing newline
-    if let Some(ch) = stdout.bytes().last() {
-        if ch == b'\n' {
-            stdout.remove(stdout.len() - 1);
-        }
+    if stdout.ends_with('\n') {
+        stdout.pop();
     }
 
     Ok(Object::String(stdout))
@@ -0,0 +1,231 @@
+use super::*;
+
+// the methods of the builtin types, which take the object as `self`
+
+mod string {
+    use super::*;
+
+    pub fn len(runtime: &mut Runtime, scope: &mut Scope) -> Result<Object> {
+        let string = get!(runtime, scope, self, String);
+        Ok(Object::Integer(string.chars().count() as IntegerType))
+    }
+
+    pub fn upper(runtime: &mut Runtime, scope: &mut Scope) -> Result<Object> {
+        Ok(Object::String(get!(runtime, scope, self, String).to_uppercase()))
+    }
+
+    pub fn lower(runtime: &mut Runtime, scope: &mut Scope) -> Result<Object> {
+        Ok(Object::String(get!(runtime, scope, self, String).to_lowercase()))
+    }
+
+    pub fn trim(runtime: &mut Runtime, scope: &mut Scope) -> Result<Object> {
+        Ok(Object::String(get!(runtime, scope, self, String).trim().to_string()))
+    }
+
+    pub fn split(runtime: &mut Runtime, scope: &mut Scope) -> Result<Object> {
+        let string = get!(runtime, scope, self, String);
+        let sep = get!(runtime, scope, sep, String);
+        Ok(Object::list(string.split(&*sep)
+            .map(|part| Object::String(part.to_string()))
+            .collect()))
+    }
+
+    pub fn contains(runtime: &mut Runtime, scope: &mut Scope) -> Result<Object> {
+        let string = get!(runtime, scope, self, String);
+        Ok(Object::Boolean(string.contains(&*get!(runtime, scope, text, String))))
+    }
+
+    pub fn starts_with(runtime: &mut Runtime, scope: &mut Scope) -> Result<Object> {
+        let string = get!(runtime, scope, self, String);
+        Ok(Object::Boolean(string.starts_with(&*get!(runtime, scope, text, String))))
+    }
+
+    pub fn ends_with(runtime: &mut Runtime, scope: &mut Scope) -> Result<Object> {
+        let string = get!(runtime, scope, self, String);
+        Ok(Object::Boolean(string.ends_with(&*get!(runtime, scope, text, String))))
+    }
+
+    pub fn replace(runtime: &mut Runtime, scope: &mut Scope) -> Result<Object> {
+        let string = get!(runtime, scope, self, String);
+        let from = get!(runtime, scope, from, String);
+        let to = get!(runtime, scope, to, String);
+        Ok(Object::String(string.replace(&*from, &to)))
+    }
+}
+
+mod list {
+    use super::*;
+
+    pub fn len(runtime: &mut Runtime, scope: &mut Scope) -> Result<Object> {
+        let list = get!(runtime, scope, self, List);
+        Ok(Object::Integer(list.borrow().len() as IntegerType))
+    }
+
+    pub fn push(runtime: &mut Runtime, scope: &mut Scope) -> Result<Object> {
+        let list = get!(runtime, scope, self, List);
+        list.borrow_mut().push(scope.get(runtime, "item")?);
+        Ok(Object::Null)
+    }
+
+    pub fn pop(runtime: &mut Runtime, scope: &mut Scope) -> Result<Object> {
+        let list = get!(runtime, scope, self, List);
+        let item = list.borrow_mut().pop();
+        item.ok_or(RuntimeError::IndexError(-1).into())
+    }
+
+    pub fn insert(runtime: &mut Runtime, scope: &mut Scope) -> Result<Object> {
+        let list = get!(runtime, scope, self, List);
+        let index = get!(runtime, scope, index, Integer);
+        let mut list = list.borrow_mut();
+        if index < 0 || list.len() < index as usize {
+            return Err(RuntimeError::IndexError(index).into());
+        }
+        list.insert(index as usize, scope.get(runtime, "item")?);
+        Ok(Object::Null)
+    }
+
+    pub fn join(runtime: &mut Runtime, scope: &mut Scope) -> Result<Object> {
+        let list = get!(runtime, scope, self, List);
+        let sep = get!(runtime, scope, sep, String);
+        let items: Vec<_> = list.borrow().iter().map(Object::to_string).collect();
+        Ok(Object::String(items.join(&sep)))
+    }
+}
+
+mod map {
+    use super::*;
+
+    pub fn len(runtime: &mut Runtime, scope: &mut Scope) -> Result<Object> {
+        let map = get!(runtime, scope, self, Map);
+        Ok(Object::Integer(map.borrow().len() as IntegerType))
+    }
+
+    pub fn keys(runtime: &mut Runtime, scope: &mut Scope) -> Result<Object> {
+        let map = get!(runtime, scope, self, Map);
+        Ok(Object::list(map.borrow().entries.iter()
+            .map(|(key, _)| key.clone().into())
+            .collect()))
+    }
+
+    pub fn values(runtime: &mut Runtime, scope: &mut Scope) -> Result<Object> {
+        let map = get!(runtime, scope, self, Map);
+        Ok(Object::list(map.borrow().entries.iter()
+            .map(|(_, value)| value.clone())
+            .collect()))
+    }
+
+    // the entries as `(key, value)` tuples
+    pub fn items(runtime: &mut Runtime, scope: &mut Scope) -> Result<Object> {
+        let map = get!(runtime, scope, self, Map);
+        Ok(Object::list(map.borrow().entries.iter()
+            .map(|(key, value)| Object::tuple(vec![key.clone().into(), value.clone()]))
+            .collect()))
+    }
+
+    pub fn contains(runtime: &mut Runtime, scope: &mut Scope) -> Result<Object> {
+        let map = get!(runtime, scope, self, Map);
+        let key = scope.get(runtime, "key")?.try_into()?;
+        Ok(Object::Boolean(map.borrow().get(&key).is_some()))
+    }
+
+    pub fn remove(runtime: &mut Runtime, scope: &mut Scope) -> Result<Object> {
+        let map = get!(runtime, scope, self, Map);
+        let key = scope.get(runtime, "key")?.try_into()?;
+        let value = map.borrow_mut().remove(&key);
+        value.ok_or(RuntimeError::KeyError(key).into())
+    }
+}
+
+mod range {
+    use super::*;
+
+    pub fn len(runtime: &mut Runtime, scope: &mut Scope) -> Result<Object> {
+        let range = get!(runtime, scope, self, Range);
+        let len = IntegerType::try_from(range.len()).map_err(|_| RuntimeError::IntegerOverflow)?;
+        Ok(Object::Integer(len))
+    }
+
+    pub fn step(runtime: &mut Runtime, scope: &mut Scope) -> Result<Object> {
+        let range = get!(runtime, scope, self, Range);
+        let step = get!(runtime, scope, step, Integer);
+        if step == 0 {
+            return Err(RuntimeError::InvalidStep(step).into());
+        }
+        Ok(Object::Range(Range { step, ..range }))
+    }
+
+    pub fn tolist(runtime: &mut Runtime, scope: &mut Scope) -> Result<Object> {
+        Ok(get!(runtime, scope, self, Range).to_list())
+    }
+}
+
+mod tuple {
+    use super::*;
+
+    pub fn len(runtime: &mut Runtime, scope: &mut Scope) -> Result<Object> {
+        let tuple = get!(runtime, scope, self, Tuple);
+        Ok(Object::Integer(tuple.len() as IntegerType))
+    }
+
+    pub fn tolist(runtime: &mut Runtime, scope: &mut Scope) -> Result<Object> {
+        Ok(Object::list(get!(runtime, scope, self, Tuple).to_vec()))
+    }
+}
+
+pub fn init(runtime: &mut Runtime) {
+    {
+        use string::*;
+        let scope = runtime.methods(Type::String);
+        add!(scope,
+            len(self);
+            upper(self);
+            lower(self);
+            trim(self);
+            split(self, sep);
+            contains(self, text);
+            starts_with(self, text);
+            ends_with(self, text);
+            replace(self, from, to);
+        );
+    }
+    {
+        use list::*;
+        let scope = runtime.methods(Type::List);
+        add!(scope,
+            len(self);
+            push(self, item);
+            pop(self);
+            insert(self, index, item);
+            join(self, sep);
+        );
+    }
+    {
+        use map::*;
+        let scope = runtime.methods(Type::Map);
+        add!(scope,
+            len(self);
+            keys(self);
+            values(self);
+            items(self);
+            contains(self, key);
+            remove(self, key);
+        );
+    }
+    {
+        use range::*;
+        let scope = runtime.methods(Type::Range);
+        add!(scope,
+            len(self);
+            step(self, step);
+            tolist(self);
+        );
+    }
+    {
+        use tuple::*;
+        let scope = runtime.methods(Type::Tuple);
+        add!(scope,
+            len(self);
+            tolist(self);
+        );
+    }
+}